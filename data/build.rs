@@ -1,10 +1,147 @@
 /// A script to dynamically generate the periodic table constant in crate::table.
-/// Reads the isotopic distribution data from data/nist_mass.json.
-use serde_json;
+/// Reads the isotopic distribution data from data/nist_mass.json, and the atomic
+/// numbers and standard atomic weights from data/nist_comp.txt.
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::io::{Cursor, Read, Write};
 use std::path;
+use std::process;
+
+/// Element names by atomic number. The placeholder symbols for 113-118 that
+/// `nist_mass.json` still uses are mapped onto their systematic names.
+const ELEMENT_NAMES: &[(&str, u8, &str)] = &[
+    ("H", 1, "Hydrogen"),
+    ("He", 2, "Helium"),
+    ("Li", 3, "Lithium"),
+    ("Be", 4, "Beryllium"),
+    ("B", 5, "Boron"),
+    ("C", 6, "Carbon"),
+    ("N", 7, "Nitrogen"),
+    ("O", 8, "Oxygen"),
+    ("F", 9, "Fluorine"),
+    ("Ne", 10, "Neon"),
+    ("Na", 11, "Sodium"),
+    ("Mg", 12, "Magnesium"),
+    ("Al", 13, "Aluminium"),
+    ("Si", 14, "Silicon"),
+    ("P", 15, "Phosphorus"),
+    ("S", 16, "Sulfur"),
+    ("Cl", 17, "Chlorine"),
+    ("Ar", 18, "Argon"),
+    ("K", 19, "Potassium"),
+    ("Ca", 20, "Calcium"),
+    ("Sc", 21, "Scandium"),
+    ("Ti", 22, "Titanium"),
+    ("V", 23, "Vanadium"),
+    ("Cr", 24, "Chromium"),
+    ("Mn", 25, "Manganese"),
+    ("Fe", 26, "Iron"),
+    ("Co", 27, "Cobalt"),
+    ("Ni", 28, "Nickel"),
+    ("Cu", 29, "Copper"),
+    ("Zn", 30, "Zinc"),
+    ("Ga", 31, "Gallium"),
+    ("Ge", 32, "Germanium"),
+    ("As", 33, "Arsenic"),
+    ("Se", 34, "Selenium"),
+    ("Br", 35, "Bromine"),
+    ("Kr", 36, "Krypton"),
+    ("Rb", 37, "Rubidium"),
+    ("Sr", 38, "Strontium"),
+    ("Y", 39, "Yttrium"),
+    ("Zr", 40, "Zirconium"),
+    ("Nb", 41, "Niobium"),
+    ("Mo", 42, "Molybdenum"),
+    ("Tc", 43, "Technetium"),
+    ("Ru", 44, "Ruthenium"),
+    ("Rh", 45, "Rhodium"),
+    ("Pd", 46, "Palladium"),
+    ("Ag", 47, "Silver"),
+    ("Cd", 48, "Cadmium"),
+    ("In", 49, "Indium"),
+    ("Sn", 50, "Tin"),
+    ("Sb", 51, "Antimony"),
+    ("Te", 52, "Tellurium"),
+    ("I", 53, "Iodine"),
+    ("Xe", 54, "Xenon"),
+    ("Cs", 55, "Caesium"),
+    ("Ba", 56, "Barium"),
+    ("La", 57, "Lanthanum"),
+    ("Ce", 58, "Cerium"),
+    ("Pr", 59, "Praseodymium"),
+    ("Nd", 60, "Neodymium"),
+    ("Pm", 61, "Promethium"),
+    ("Sm", 62, "Samarium"),
+    ("Eu", 63, "Europium"),
+    ("Gd", 64, "Gadolinium"),
+    ("Tb", 65, "Terbium"),
+    ("Dy", 66, "Dysprosium"),
+    ("Ho", 67, "Holmium"),
+    ("Er", 68, "Erbium"),
+    ("Tm", 69, "Thulium"),
+    ("Yb", 70, "Ytterbium"),
+    ("Lu", 71, "Lutetium"),
+    ("Hf", 72, "Hafnium"),
+    ("Ta", 73, "Tantalum"),
+    ("W", 74, "Tungsten"),
+    ("Re", 75, "Rhenium"),
+    ("Os", 76, "Osmium"),
+    ("Ir", 77, "Iridium"),
+    ("Pt", 78, "Platinum"),
+    ("Au", 79, "Gold"),
+    ("Hg", 80, "Mercury"),
+    ("Tl", 81, "Thallium"),
+    ("Pb", 82, "Lead"),
+    ("Bi", 83, "Bismuth"),
+    ("Po", 84, "Polonium"),
+    ("At", 85, "Astatine"),
+    ("Rn", 86, "Radon"),
+    ("Fr", 87, "Francium"),
+    ("Ra", 88, "Radium"),
+    ("Ac", 89, "Actinium"),
+    ("Th", 90, "Thorium"),
+    ("Pa", 91, "Protactinium"),
+    ("U", 92, "Uranium"),
+    ("Np", 93, "Neptunium"),
+    ("Pu", 94, "Plutonium"),
+    ("Am", 95, "Americium"),
+    ("Cm", 96, "Curium"),
+    ("Bk", 97, "Berkelium"),
+    ("Cf", 98, "Californium"),
+    ("Es", 99, "Einsteinium"),
+    ("Fm", 100, "Fermium"),
+    ("Md", 101, "Mendelevium"),
+    ("No", 102, "Nobelium"),
+    ("Lr", 103, "Lawrencium"),
+    ("Rf", 104, "Rutherfordium"),
+    ("Db", 105, "Dubnium"),
+    ("Sg", 106, "Seaborgium"),
+    ("Bh", 107, "Bohrium"),
+    ("Hs", 108, "Hassium"),
+    ("Mt", 109, "Meitnerium"),
+    ("Ds", 110, "Darmstadtium"),
+    ("Rg", 111, "Roentgenium"),
+    ("Cn", 112, "Copernicium"),
+    ("Nh", 113, "Nihonium"),
+    ("Uut", 113, "Ununtrium"),
+    ("Fl", 114, "Flerovium"),
+    ("Uuq", 114, "Ununquadium"),
+    ("Mc", 115, "Moscovium"),
+    ("Uup", 115, "Ununpentium"),
+    ("Lv", 116, "Livermorium"),
+    ("Uuh", 116, "Ununhexium"),
+    ("Ts", 117, "Tennessine"),
+    ("Uus", 117, "Ununseptium"),
+    ("Og", 118, "Oganesson"),
+    ("Uuo", 118, "Ununoctium"),
+    ("H+", 0, "Proton"),
+    ("e*", 0, "Electron"),
+];
+
+/// Isotopes kept despite having no natural abundance, because they are common
+/// enough as labels to have their own symbols
+const LABEL_ISOTOPES: &[(&str, u16)] = &[("H", 3)];
 
 fn load_from_file(path: &path::Path) -> Map<String, Value> {
     let mut reader = fs::File::open(path).unwrap();
@@ -12,7 +149,57 @@ fn load_from_file(path: &path::Path) -> Map<String, Value> {
     reader.read_to_string(&mut buf).unwrap();
     let val: Value = serde_json::from_str(&buf).unwrap();
     let m: Map<String, Value> = val.as_object().unwrap().clone();
-    return m;
+    m
+}
+
+/// Read the "Standard Atomic Weight" of each atomic number from the NIST listing
+fn load_standard_weights(path: &path::Path) -> HashMap<u8, String> {
+    let text = fs::read_to_string(path).unwrap();
+    let mut weights = HashMap::new();
+    let mut atomic_number = None;
+    for line in text.lines() {
+        match line.split_once('=') {
+            Some((key, value)) if key.trim() == "Atomic Number" => {
+                atomic_number = Some(value.trim().parse::<u8>().unwrap());
+            }
+            Some((key, value)) if key.trim() == "Standard Atomic Weight" => {
+                weights
+                    .entry(atomic_number.unwrap())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+    weights
+}
+
+/// Render a NIST standard atomic weight as a `StandardAtomicWeight` expression
+fn format_standard_weight(value: Option<&String>) -> String {
+    let value = match value {
+        Some(value) if !value.is_empty() => value.as_str(),
+        _ => return "StandardAtomicWeight::Unknown".to_string(),
+    };
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return match inner.split_once(',') {
+            Some((lower, upper)) => format!(
+                "StandardAtomicWeight::Interval {{ lower: {:?}, upper: {:?} }}",
+                lower.trim().parse::<f64>().unwrap(),
+                upper.trim().parse::<f64>().unwrap()
+            ),
+            None => format!(
+                "StandardAtomicWeight::MassNumber({})",
+                inner.trim().parse::<u16>().unwrap()
+            ),
+        };
+    }
+    let (digits, uncertainty) = value.split_once('(').unwrap_or((value, "0)"));
+    let decimals = digits.split_once('.').map(|(_, d)| d.len()).unwrap_or(0);
+    let uncertainty: f64 = uncertainty.trim_end_matches(')').parse().unwrap();
+    format!(
+        "StandardAtomicWeight::Value {{ value: {:?}, uncertainty: {:?} }}",
+        digits.parse::<f64>().unwrap(),
+        uncertainty / 10f64.powi(decimals as i32)
+    )
 }
 
 //' A Dummy copy of the implementation for convenience.
@@ -24,19 +211,31 @@ struct Isotope {
     pub neutron_shift: i8,
 }
 
-
 fn write_prelude(buffer: &mut Cursor<Vec<u8>>) {
-    write!(buffer, r#"
-use lazy_static::lazy_static;
-use crate::element::{{Element, Isotope, PeriodicTable}};
-
+    write!(
+        buffer,
+        r#"use crate::element::{{Element, Isotope, PeriodicTable, StandardAtomicWeight}};
+use std::sync::LazyLock;
 
+#[allow(clippy::approx_constant)]
 pub fn populate_periodic_table(table: &mut PeriodicTable) {{
-"#).unwrap();
+"#
+    )
+    .unwrap();
 }
 
+fn prepare_element(
+    buffer: &mut Cursor<Vec<u8>>,
+    symbol: &String,
+    isotopes: &Value,
+    weights: &HashMap<u8, String>,
+) {
+    let (_, element_number, name) = ELEMENT_NAMES
+        .iter()
+        .find(|(sym, _, _)| sym == symbol)
+        .unwrap_or_else(|| panic!("No name is known for {}", symbol));
+    let standard_weight = format_standard_weight(weights.get(element_number));
 
-fn prepare_element(buffer: &mut Cursor<Vec<u8>>, symbol: &String, isotopes: &Value) {
     let mut isos: Vec<Isotope> = Vec::new();
     let iso = isotopes.as_object().unwrap();
     let mut reference_entry = Isotope {
@@ -51,7 +250,7 @@ fn prepare_element(buffer: &mut Cursor<Vec<u8>>, symbol: &String, isotopes: &Val
             neutrons: isonum,
             ..Default::default()
         };
-        if x.abundance == 0.0 {
+        if x.abundance == 0.0 && !LABEL_ISOTOPES.contains(&(symbol.as_str(), isonum)) {
             continue;
         }
         if isonum == 0 {
@@ -65,22 +264,18 @@ fn prepare_element(buffer: &mut Cursor<Vec<u8>>, symbol: &String, isotopes: &Val
     if n > 0 {
         let most_abundant_neutron_count = isos[n - 1].neutrons;
         let most_abundant_mass = isos[n - 1].mass;
-        let mut element_number = 0;
         for y in &mut isos {
             y.neutron_shift = ((y.neutrons as i32) - (most_abundant_neutron_count as i32)) as i8;
-            if y.neutron_shift == 0 {
-                element_number = y.neutrons;
-            }
         }
-        writeln!(buffer, "\n\tlet mut elt = Element {{ symbol: String::from(\"{}\"), most_abundant_isotope: {}, most_abundant_mass: {:.6}, element_number: {},..Default::default() }};",
-                 symbol, most_abundant_neutron_count, most_abundant_mass, element_number).unwrap();
+        writeln!(buffer, "\n\tlet mut elt = Element {{ symbol: String::from(\"{}\"), most_abundant_isotope: {}, most_abundant_mass: {:.6}, element_number: {}, name: String::from(\"{}\"), standard_atomic_weight: {}, ..Default::default() }};",
+                 symbol, most_abundant_neutron_count, most_abundant_mass, element_number, name, standard_weight).unwrap();
         for y in &isos {
             writeln!(buffer, "\telt.isotopes.insert({}, Isotope {{ mass: {:.6}, abundance: {:.6}, neutrons: {}, neutron_shift: {} }});",
                         y.neutrons, y.mass, y.abundance, y.neutrons, y.neutron_shift).unwrap();
         }
     } else {
-        writeln!(buffer, "\tlet mut elt = Element {{ symbol: String::from(\"{}\"), most_abundant_isotope: {}, most_abundant_mass: {:.6}, ..Default::default() }};",
-                 symbol, 0, reference_entry.mass).unwrap();
+        writeln!(buffer, "\tlet mut elt = Element {{ symbol: String::from(\"{}\"), most_abundant_isotope: {}, most_abundant_mass: {:.6}, element_number: {}, name: String::from(\"{}\"), standard_atomic_weight: {}, ..Default::default() }};",
+                 symbol, 0, reference_entry.mass, element_number, name, standard_weight).unwrap();
         writeln!(buffer, "\telt.isotopes.insert({}, Isotope {{ mass: {:.6}, abundance: {:.6}, neutrons: {}, neutron_shift: {} }});",
                     reference_entry.neutrons, reference_entry.mass, reference_entry.abundance, reference_entry.neutrons,
                     reference_entry.neutron_shift).unwrap();
@@ -91,24 +286,33 @@ fn prepare_element(buffer: &mut Cursor<Vec<u8>>, symbol: &String, isotopes: &Val
 fn main() {
     println!("cargo:rerun-if-changed=src/table.rs");
     let elements = load_from_file(path::Path::new("data/nist_mass.json"));
+    let weights = load_standard_weights(path::Path::new("data/nist_comp.txt"));
     let mut buffer = Cursor::new(Vec::new());
     write_prelude(&mut buffer);
     for (key, val) in elements.iter() {
-        prepare_element(&mut buffer, key, val);
+        prepare_element(&mut buffer, key, val, &weights);
     }
-    write!(&mut buffer, r#"}}
+    write!(
+        &mut buffer,
+        r#"}}
 
-lazy_static! {{
-    pub static ref PERIODIC_TABLE: PeriodicTable = {{
-        let mut t = PeriodicTable::new();
-        populate_periodic_table(&mut t);
-        t
-    }};
-}}"#).unwrap();
+pub static PERIODIC_TABLE: LazyLock<PeriodicTable> = LazyLock::new(|| {{
+    let mut t = PeriodicTable::new();
+    populate_periodic_table(&mut t);
+    t
+}});
+"#
+    )
+    .unwrap();
     buffer.set_position(0);
     let mut out = String::new();
     buffer.read_to_string(&mut out).unwrap();
     let mut destination = fs::File::create("src/table.rs").unwrap();
-    destination.write(out.as_bytes()).unwrap();
-    process::Command::new("rustfmt").arg("src/table.rs").status().unwrap();
+    destination.write_all(out.as_bytes()).unwrap();
+    process::Command::new("rustfmt")
+        .arg("--edition")
+        .arg("2021")
+        .arg("src/table.rs")
+        .status()
+        .unwrap();
 }
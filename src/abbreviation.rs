@@ -0,0 +1,355 @@
+/*!
+Abbreviations for groups of atoms, like `Me` for a methyl group or `HexNAc` for an
+N-acetylhexosamine residue, which may be written in formulae in place of their
+elemental compositions.
+
+```rust
+use chemical_elements::parse_formula;
+use chemical_elements::abbreviation::AbbreviationRegistry;
+
+let mut abbreviations = AbbreviationRegistry::builtin();
+let benzyl_acetate = abbreviations.parse("PhCH2OAc").unwrap();
+assert_eq!(benzyl_acetate, parse_formula("C9H10O2").unwrap());
+
+abbreviations.register_formula("Bn", "PhCH2").unwrap();
+assert_eq!(abbreviations.parse("BnOAc").unwrap(), benzyl_acetate);
+```
+
+# Precedence
+
+An abbreviation is recognized wherever an element symbol could begin, and is read
+like a parenthesized group, so it may be followed by a count like `Me2`.
+
+1. When several abbreviations could begin at the same place, the longest is used,
+   so `HexNAc` is not read as `Hex` followed by `NAc`.
+2. An abbreviation must end at the end of the formula or before a character which
+   is not a lowercase letter, so `Me` is never read from the start of `Mes`.
+3. An abbreviation which is also an element symbol, like `Ac` for both the acetyl
+   group and actinium, is read according to [`AbbreviationPrecedence`]. By default
+   the abbreviation is used.
+4. An element with an isotope written before it, like `^13C` or `[13C]`, is always
+   read as an element.
+
+Formulae parsed without a registry, like those given to [`parse_formula`](crate::parse_formula),
+never use abbreviations.
+*/
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use fnv::FnvBuildHasher;
+
+use crate::element_specification::resolve_symbol;
+use crate::formula::FormulaParser;
+use crate::{ChemicalComposition, FormulaParserError, PeriodicTable, PERIODIC_TABLE};
+
+/// The abbreviations known to [`AbbreviationRegistry::builtin`], and the formulae they stand for
+const BUILTIN_ABBREVIATIONS: &[(&str, &str)] = &[
+    // Substituent groups, which each take the place of a hydrogen
+    ("Me", "CH3"),
+    ("Et", "C2H5"),
+    ("Ph", "C6H5"),
+    ("Ac", "C2H3O"),
+    ("Boc", "C5H9O2"),
+    ("TMS", "C3H9Si"),
+    // Monosaccharide residues, which have lost a water to the glycosidic bond
+    ("Hex", "C6H10O5"),
+    ("HexNAc", "C8H13NO5"),
+    ("Fuc", "C6H10O4"),
+    ("NeuAc", "C11H17NO8"),
+    // Solvents and acids, which are common adducts
+    ("ACN", "C2H3N"),
+    ("MeOH", "CH4O"),
+    ("IsoProp", "C3H8O"),
+    ("DMSO", "C2H6OS"),
+    ("FA", "CH2O2"),
+    ("Hac", "C2H4O2"),
+    ("HAc", "C2H4O2"),
+    ("TFA", "C2HF3O2"),
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How to read an abbreviation which is also an element symbol, like `Ac`
+pub enum AbbreviationPrecedence {
+    /// Read the symbol as the abbreviation
+    #[default]
+    Abbreviations,
+    /// Read the symbol as the element
+    Elements,
+}
+
+#[derive(Debug, Clone)]
+/// An error that occurred while registering an abbreviation
+pub enum AbbreviationError {
+    /// The name was not an uppercase letter followed by letters
+    InvalidName(String),
+    /// The formula of the abbreviation could not be parsed
+    FormulaError(String, Box<FormulaParserError>),
+}
+
+impl Display for AbbreviationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(
+                f,
+                "Invalid abbreviation {name:?}, abbreviations must be an uppercase letter followed by letters"
+            ),
+            Self::FormulaError(name, err) => {
+                write!(f, "Failed to parse the formula of {name:?}: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AbbreviationError {}
+
+#[derive(Debug, Clone)]
+/// A set of abbreviations that may be used in formulae parsed with [`AbbreviationRegistry::parse`]
+pub struct AbbreviationRegistry<'lifespan> {
+    abbreviations: HashMap<String, ChemicalComposition<'lifespan>, FnvBuildHasher>,
+    /// How to read an abbreviation which is also an element symbol
+    pub precedence: AbbreviationPrecedence,
+    periodic_table: &'lifespan PeriodicTable,
+}
+
+impl Default for AbbreviationRegistry<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'lifespan> AbbreviationRegistry<'lifespan> {
+    /// Create an empty registry using the default periodic table
+    pub fn new() -> AbbreviationRegistry<'static> {
+        AbbreviationRegistry::with_table(&PERIODIC_TABLE)
+    }
+
+    /// Create an empty registry using `periodic_table`
+    pub fn with_table(periodic_table: &'lifespan PeriodicTable) -> Self {
+        Self {
+            abbreviations: HashMap::default(),
+            precedence: AbbreviationPrecedence::default(),
+            periodic_table,
+        }
+    }
+
+    /// Create a registry of the common abbreviations `Me`, `Et`, `Ph`, `Ac`, `Boc`
+    /// and `TMS`, the monosaccharide residues `Hex`, `HexNAc`, `Fuc` and `NeuAc`, and
+    /// the adduct solvents `ACN`, `MeOH`, `IsoProp`, `DMSO`, `FA`, `HAc` and `TFA`,
+    /// using the default periodic table
+    pub fn builtin() -> AbbreviationRegistry<'static> {
+        AbbreviationRegistry::builtin_with_table(&PERIODIC_TABLE)
+    }
+
+    /// Create a registry of the common abbreviations, using `periodic_table`. Any
+    /// abbreviation using an element missing from `periodic_table` is left out.
+    pub fn builtin_with_table(periodic_table: &'lifespan PeriodicTable) -> Self {
+        let mut registry = Self::with_table(periodic_table);
+        for (name, formula) in BUILTIN_ABBREVIATIONS {
+            registry.register_formula(*name, formula).ok();
+        }
+        registry
+    }
+
+    /// Register `composition` under `name`, returning the composition previously
+    /// registered under that name if there was one
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        composition: ChemicalComposition<'lifespan>,
+    ) -> Result<Option<ChemicalComposition<'lifespan>>, AbbreviationError> {
+        let name: String = name.into();
+        let mut chars = name.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+            && chars.all(|c| c.is_ascii_alphabetic());
+        if !valid {
+            return Err(AbbreviationError::InvalidName(name));
+        }
+        Ok(self.abbreviations.insert(name, composition))
+    }
+
+    /// Register the composition of `formula` under `name`. The formula may use the
+    /// abbreviations already registered.
+    pub fn register_formula(
+        &mut self,
+        name: impl Into<String>,
+        formula: &str,
+    ) -> Result<Option<ChemicalComposition<'lifespan>>, AbbreviationError> {
+        let name: String = name.into();
+        match self.parse(formula) {
+            Ok(composition) => self.register(name, composition),
+            Err(err) => Err(AbbreviationError::FormulaError(name, Box::new(err))),
+        }
+    }
+
+    /// Remove the abbreviation `name`, returning its composition if it was registered
+    pub fn remove(&mut self, name: &str) -> Option<ChemicalComposition<'lifespan>> {
+        self.abbreviations.remove(name)
+    }
+
+    /// The periodic table the abbreviations and formulae are read with
+    pub fn periodic_table(&self) -> &'lifespan PeriodicTable {
+        self.periodic_table
+    }
+
+    /// The composition registered under `name`
+    pub fn get(&self, name: &str) -> Option<&ChemicalComposition<'lifespan>> {
+        self.abbreviations.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.abbreviations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abbreviations.is_empty()
+    }
+
+    /// Iterate over the registered abbreviations and their compositions, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ChemicalComposition<'lifespan>)> {
+        self.abbreviations
+            .iter()
+            .map(|(name, composition)| (name.as_str(), composition))
+    }
+
+    /// Parse `formula`, expanding the registered abbreviations in it
+    pub fn parse(
+        &self,
+        formula: &str,
+    ) -> Result<ChemicalComposition<'lifespan>, FormulaParserError> {
+        let mut parser = FormulaParser::default();
+        parser.parse_formula_with_abbreviations_generic(formula, self.periodic_table, Some(self))
+    }
+
+    /// Parse `formula` with an optional trailing charge, expanding the registered
+    /// abbreviations in it
+    pub fn parse_charged(
+        &self,
+        formula: &str,
+    ) -> Result<(ChemicalComposition<'lifespan>, i32), FormulaParserError> {
+        let mut parser = FormulaParser {
+            allow_charge: true,
+            ..FormulaParser::default()
+        };
+        let composition = parser.parse_formula_with_abbreviations_generic(
+            formula,
+            self.periodic_table,
+            Some(self),
+        )?;
+        Ok((composition, parser.charge))
+    }
+
+    /// Find the abbreviation which `formula` starts with, following the precedence
+    /// rules described in the [module documentation](self)
+    pub(crate) fn find_prefix(
+        &self,
+        formula: &str,
+    ) -> Option<(&str, &ChemicalComposition<'lifespan>)> {
+        let word = formula
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .map_or(formula, |end| &formula[..end]);
+        (1..=word.len())
+            .rev()
+            .filter(|end| !word[*end..].starts_with(|c: char| c.is_ascii_lowercase()))
+            .find_map(|end| {
+                let (name, composition) = self.abbreviations.get_key_value(&word[..end])?;
+                let is_element = resolve_symbol(name, self.periodic_table).is_some();
+                if is_element && self.precedence == AbbreviationPrecedence::Elements {
+                    None
+                } else {
+                    Some((name.as_str(), composition))
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_formula;
+
+    #[test]
+    fn test_builtin() {
+        let abbreviations = AbbreviationRegistry::builtin();
+        assert_eq!(abbreviations.len(), BUILTIN_ABBREVIATIONS.len());
+
+        for (formula, expected) in [
+            ("MeOH", "CH4O"),
+            ("Et2O", "C4H10O"),
+            ("PhCH2OAc", "C9H10O2"),
+            ("TMSCl", "C3H9SiCl"),
+            ("BocNHMe", "C6H13NO2"),
+            ("Hex2HexNAc", "C20H33NO15"),
+            ("(Hex)3(HexNAc)2FucNeuAc", "C51H83N3O37"),
+            ("[Co(NMe3)6]Cl3", "CoC18N6H54Cl3"),
+            ("HexNAc·H2O", "C8H15NO6"),
+        ] {
+            assert_eq!(
+                abbreviations.parse(formula).unwrap(),
+                parse_formula(expected).unwrap(),
+                "{formula}"
+            );
+        }
+
+        let (composition, charge) = abbreviations.parse_charged("Me4N+").unwrap();
+        assert_eq!(charge, 1);
+        assert_eq!(composition, parse_formula("C4H12N").unwrap());
+
+        assert!(abbreviations.parse("Mes").is_err());
+        assert!(parse_formula("PhCH2OAc").is_err());
+    }
+
+    #[test]
+    fn test_precedence() {
+        let mut abbreviations = AbbreviationRegistry::builtin();
+        let acetyl = abbreviations.parse("AcCl").unwrap();
+        assert_eq!(acetyl, parse_formula("C2H3OCl").unwrap());
+
+        abbreviations.precedence = AbbreviationPrecedence::Elements;
+        let actinium = abbreviations.parse("AcCl3").unwrap();
+        assert_eq!(actinium, parse_formula("AcCl3").unwrap());
+        assert_eq!(
+            abbreviations.parse("MeOH").unwrap(),
+            parse_formula("CH4O").unwrap()
+        );
+
+        abbreviations.precedence = AbbreviationPrecedence::Abbreviations;
+        let labeled = abbreviations.parse("[13C]H3OMe").unwrap();
+        assert_eq!(labeled["C[13]"], 1);
+        assert_eq!(labeled["C"], 1);
+    }
+
+    #[test]
+    fn test_register() {
+        let mut abbreviations = AbbreviationRegistry::new();
+        assert!(abbreviations.is_empty());
+        assert!(abbreviations.parse("MeOH").is_err());
+
+        abbreviations.register_formula("Me", "CH3").unwrap();
+        abbreviations.register_formula("OMe", "OMe").unwrap();
+        assert_eq!(
+            abbreviations.get("OMe"),
+            Some(&parse_formula("CH3O").unwrap())
+        );
+        assert_eq!(
+            abbreviations.parse("HOMe").unwrap(),
+            parse_formula("CH4O").unwrap()
+        );
+
+        let previous = abbreviations
+            .register("Me", parse_formula("CD3").unwrap())
+            .unwrap();
+        assert_eq!(previous, Some(parse_formula("CH3").unwrap()));
+        assert_eq!(abbreviations.parse("Me").unwrap()["H[2]"], 3);
+
+        assert!(matches!(
+            abbreviations.register("me", parse_formula("CH3").unwrap()),
+            Err(AbbreviationError::InvalidName(_))
+        ));
+        assert!(matches!(
+            abbreviations.register_formula("Xx", "Qq"),
+            Err(AbbreviationError::FormulaError(_, _))
+        ));
+        assert!(abbreviations.remove("OMe").is_some());
+        assert_eq!(abbreviations.len(), 1);
+    }
+}
@@ -8,7 +8,7 @@ use std::slice::{Iter as VecIter, IterMut as VecIterMut};
 use std::str::FromStr;
 
 #[cfg(feature = "serde")]
-use serde_with::SerializeDisplay;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 use crate::formula::FormulaParser;
 use crate::{
@@ -17,23 +17,12 @@ use crate::{
 };
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(SerializeDisplay))]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
 pub enum ChemicalComposition<'lifespan> {
     Vec(ChemicalCompositionVec<'lifespan>),
     Map(ChemicalCompositionMap<'lifespan>),
 }
 
-impl<'lifespan> PartialEq for ChemicalComposition<'lifespan> {
-    #[inline]
-    fn eq(&self, other: &ChemicalComposition<'lifespan>) -> bool {
-        if self.len() != other.len() {
-            false
-        } else {
-            self.iter().all(|(k, v)| other.get(k) == *v)
-        }
-    }
-}
-
 impl Default for ChemicalComposition<'_> {
     fn default() -> Self {
         ChemicalComposition::Vec(ChemicalCompositionVec::default())
@@ -145,6 +134,52 @@ impl<'transient, 'inner: 'transient, 'lifespan: 'inner> ChemicalComposition<'lif
         }
     }
 
+    #[inline]
+    /**
+    Explicitly calculate the average mass of the chemical composition, ignoring
+    any caching.
+    */
+    pub fn calc_average_mass(&self) -> f64 {
+        match self {
+            ChemicalComposition::Vec(v) => v.calc_average_mass(),
+            ChemicalComposition::Map(m) => m.calc_average_mass(),
+        }
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition. If the average mass cache
+    has been populated, return that instead of repeating the calculation.
+    */
+    pub fn average_mass(&self) -> f64 {
+        match self {
+            ChemicalComposition::Vec(v) => v.average_mass(),
+            ChemicalComposition::Map(m) => m.average_mass(),
+        }
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition, and cache it,
+    or reuse the cached value. This requires mutability, so this method
+    must be called explicitly.
+    */
+    pub fn faverage_mass(&mut self) -> f64 {
+        match self {
+            ChemicalComposition::Vec(v) => v.faverage_mass(),
+            ChemicalComposition::Map(m) => m.faverage_mass(),
+        }
+    }
+
+    #[inline]
+    /// Test if the average mass cache is populated.
+    pub fn has_average_mass_cached(&self) -> bool {
+        match self {
+            ChemicalComposition::Vec(v) => v.has_average_mass_cached(),
+            ChemicalComposition::Map(m) => m.has_average_mass_cached(),
+        }
+    }
+
     #[inline]
     pub(crate) fn _add_from(&mut self, other: &'transient ChemicalCompositionVec<'lifespan>) {
         for (key, val) in other.iter() {
@@ -167,6 +202,17 @@ impl<'transient, 'inner: 'transient, 'lifespan: 'inner> ChemicalComposition<'lif
         }
     }
 
+    /// Remove the elements whose count is zero, like those left by subtraction.
+    ///
+    /// Equality, hashing and formatting already treat these elements as absent,
+    /// but they are still counted by [`ChemicalComposition::len`].
+    pub fn prune_zeros(&mut self) {
+        match self {
+            ChemicalComposition::Vec(c) => c.prune_zeros(),
+            ChemicalComposition::Map(c) => c.prune_zeros(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self {
             ChemicalComposition::Vec(i) => i.is_empty(),
@@ -482,6 +528,39 @@ impl<'inner, 'lifespan: 'inner> ChemicalCompositionRef<'inner, 'lifespan> {
         }
     }
 
+    #[inline]
+    /**
+    Explicitly calculate the average mass of the chemical composition, ignoring
+    any caching.
+    */
+    pub fn calc_average_mass(&self) -> f64 {
+        match self {
+            ChemicalCompositionRef::Vec(v) => v.calc_average_mass(),
+            ChemicalCompositionRef::Map(m) => m.calc_average_mass(),
+        }
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition. If the average mass cache
+    has been populated, return that instead of repeating the calculation.
+    */
+    pub fn average_mass(&self) -> f64 {
+        match self {
+            ChemicalCompositionRef::Vec(v) => v.average_mass(),
+            ChemicalCompositionRef::Map(m) => m.average_mass(),
+        }
+    }
+
+    #[inline]
+    /// Test if the average mass cache is populated.
+    pub fn has_average_mass_cached(&self) -> bool {
+        match self {
+            ChemicalCompositionRef::Vec(v) => v.has_average_mass_cached(),
+            ChemicalCompositionRef::Map(m) => m.has_average_mass_cached(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self {
             ChemicalCompositionRef::Vec(i) => i.is_empty(),
@@ -618,6 +697,23 @@ mod test {
         assert!((mass - calc).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_average_mass() {
+        let mut case = ChemicalComposition::from(vec![("O", 1), ("H", 2)]);
+        let mass = 18.01528;
+
+        assert!(!case.has_average_mass_cached());
+        let calc = case.faverage_mass();
+        assert!((mass - calc).abs() < 1e-3);
+        assert!(case.has_average_mass_cached());
+        assert!(!case.has_mass_cached());
+
+        case.inc("H[2]".parse().unwrap(), 1);
+        assert!(!case.has_average_mass_cached());
+        let calc = case.average_mass();
+        assert!((mass + 2.0141017 - calc).abs() < 1e-3);
+    }
+
     #[test]
     fn test_add() {
         let case = ChemicalComposition::from(vec![("O", 1), ("H", 2)]);
@@ -644,4 +740,90 @@ mod test {
         let combo = &case * 2;
         assert_eq!(ctrl, combo);
     }
+
+    #[test]
+    fn test_prune_zeros() {
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        let water = ChemicalComposition::parse("H2O").unwrap();
+        for mut case in [glucose.clone(), glucose.clone().into_map()] {
+            case -= &(&water * 6);
+            assert_eq!(case.len(), 3);
+            assert_eq!(case.to_string(), "C6");
+            assert_eq!(case, ChemicalComposition::parse("C6").unwrap());
+            assert!(case.is_nonnegative());
+
+            case.prune_zeros();
+            assert_eq!(case.len(), 1);
+            assert_eq!(case.to_string(), "C6");
+        }
+
+        let mut delta = &water - &glucose;
+        assert!(!delta.is_nonnegative());
+        let negatives: Vec<_> = delta
+            .negative_elements()
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        assert_eq!(
+            negatives,
+            vec![("H".to_string(), -10), ("C".to_string(), -6), ("O".to_string(), -5)]
+        );
+        delta = &delta + &glucose;
+        assert_eq!(delta, water);
+        assert!(delta.is_nonnegative());
+    }
+
+    #[test]
+    fn test_hash_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let water = ChemicalComposition::parse("H2O").unwrap();
+        let mut padded = ChemicalComposition::Map(ChemicalCompositionMap::from(vec![
+            ("O", 1),
+            ("H", 2),
+        ]));
+        padded["C"] = 0;
+        assert_eq!(water, padded);
+        assert_eq!(padded, water);
+
+        let heavy = ChemicalComposition::parse("H[2]2O").unwrap();
+        let candidates = [
+            water.clone(),
+            padded,
+            heavy.clone(),
+            ChemicalComposition::parse("HHO").unwrap(),
+        ];
+        let unique: HashSet<_> = candidates.iter().cloned().collect();
+        assert_eq!(unique.len(), 2);
+        assert!(unique.contains(&water));
+        assert!(unique.contains(&heavy));
+
+        let ordered: BTreeSet<_> = candidates.into_iter().collect();
+        assert_eq!(ordered.len(), 2);
+        assert!(water < heavy);
+
+        let methane = ChemicalComposition::parse("CH4").unwrap();
+        let ethane = ChemicalComposition::parse("C2H6").unwrap();
+        assert!(methane < ethane);
+        assert_eq!(
+            methane.cmp(&ChemicalComposition::Map(ChemicalCompositionMap::from(vec![
+                ("H", 4),
+                ("C", 1)
+            ]))),
+            std::cmp::Ordering::Equal
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_negative_counts() {
+        let delta = ChemicalComposition::parse("H-2O-1").unwrap();
+        let text = serde_json::to_string(&delta).unwrap();
+        assert_eq!(text, "\"H-2O-1\"");
+        let dup: ChemicalComposition = serde_json::from_str(&text).unwrap();
+        assert_eq!(dup, delta);
+
+        let dup: ChemicalCompositionMap = serde_json::from_str(&text).unwrap();
+        assert_eq!(dup["O"], -1);
+    }
 }
@@ -0,0 +1,499 @@
+/*!
+Adduct ions like `[M+H]+`, `[M+Na]+` or `[2M-H2O+H]+`, and the conversions between
+the neutral mass of a molecule and the m/z of its adducts.
+
+```rust
+# use chemical_elements::ChemicalComposition;
+use chemical_elements::adduct::Adduct;
+
+let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+let sodiated: Adduct = "[M+Na]+".parse().unwrap();
+let mz = sodiated.mz(&glucose);
+assert!((mz - 203.05261).abs() < 1e-4);
+assert!((sodiated.neutral_mass(mz) - glucose.mass()).abs() < 1e-6);
+```
+*/
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::abbreviation::AbbreviationRegistry;
+use crate::{
+    ChemicalComposition, ChemicalCompositionLike, ElementSpecification, FormulaParserError,
+    PeriodicTable, PERIODIC_TABLE,
+};
+
+/// The abbreviations adducts are parsed with using the default periodic table
+static ADDUCT_ABBREVIATIONS: LazyLock<AbbreviationRegistry<'static>> =
+    LazyLock::new(AbbreviationRegistry::builtin);
+
+/// The adducts commonly observed in positive mode electrospray ionization
+const POSITIVE_ADDUCTS: &[&str] = &[
+    "[M+H]+",
+    "[M+Na]+",
+    "[M+K]+",
+    "[M+NH4]+",
+    "[M+Li]+",
+    "[M+H-H2O]+",
+    "[M+H-2H2O]+",
+    "[M+ACN+H]+",
+    "[M+MeOH+H]+",
+    "[M+ACN+Na]+",
+    "[M]+",
+    "[M+2H]2+",
+    "[M+H+Na]2+",
+    "[M+H+NH4]2+",
+    "[M+2Na]2+",
+    "[M+3H]3+",
+    "[2M+H]+",
+    "[2M+Na]+",
+    "[2M+K]+",
+    "[2M+NH4]+",
+];
+
+/// The adducts commonly observed in negative mode electrospray ionization
+const NEGATIVE_ADDUCTS: &[&str] = &[
+    "[M-H]-",
+    "[M+Cl]-",
+    "[M+FA-H]-",
+    "[M+Hac-H]-",
+    "[M-H2O-H]-",
+    "[M+Na-2H]-",
+    "[M+K-2H]-",
+    "[M+Br]-",
+    "[M+TFA-H]-",
+    "[M]-",
+    "[M-2H]2-",
+    "[M-3H]3-",
+    "[2M-H]-",
+    "[2M+FA-H]-",
+    "[2M+Hac-H]-",
+];
+
+static COMMON_ADDUCTS: LazyLock<Vec<Adduct<'static>>> = LazyLock::new(|| {
+    POSITIVE_ADDUCTS
+        .iter()
+        .chain(NEGATIVE_ADDUCTS.iter())
+        .map(|s| s.parse().unwrap())
+        .collect()
+});
+
+#[derive(Debug, Clone)]
+/// An error that occurred while parsing an [`Adduct`] from bracket notation
+pub enum AdductParsingError {
+    /// The adduct was not enclosed in `[]`
+    MissingBrackets,
+    /// The first term did not name the molecule, like `M` or `2M`
+    MissingMolecule,
+    /// A term between the brackets could not be understood
+    InvalidTerm(String),
+    /// The charge following the brackets could not be understood
+    InvalidCharge(String),
+    /// A term's formula could not be parsed
+    FormulaError(String, Box<FormulaParserError>),
+    /// The periodic table has no `e*` pseudo-element to account for the charge with
+    MissingElectron,
+}
+
+impl Display for AdductParsingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBrackets => f.write_str("Adducts must be enclosed in []"),
+            Self::MissingMolecule => f.write_str("Adducts must begin with the molecule, M"),
+            Self::InvalidTerm(term) => write!(f, "Invalid adduct term {term:?}"),
+            Self::InvalidCharge(charge) => write!(f, "Invalid adduct charge {charge:?}"),
+            Self::FormulaError(term, err) => {
+                write!(f, "Failed to parse the formula of {term:?}: {err}")
+            }
+            Self::MissingElectron => {
+                f.write_str("The periodic table has no electron pseudo-element, e*")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdductParsingError {}
+
+/// Split a leading multiplier like the `2` in `2H` from the rest of a term
+fn split_multiplier(term: &str) -> Result<(i32, &str), AdductParsingError> {
+    let digits = term.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        Ok((1, term))
+    } else {
+        let count = term[..digits]
+            .parse()
+            .map_err(|_| AdductParsingError::InvalidTerm(term.to_string()))?;
+        Ok((count, &term[digits..]))
+    }
+}
+
+/// Parse a charge suffix like `+`, `2-`, `+2` or `++`
+fn parse_charge(charge: &str) -> Result<i32, AdductParsingError> {
+    let invalid = || AdductParsingError::InvalidCharge(charge.to_string());
+    let sign = match (charge.find('+'), charge.find('-')) {
+        (Some(_), None) => 1,
+        (None, Some(_)) => -1,
+        _ => return Err(invalid()),
+    };
+    let digits = charge.trim_matches(|c| c == '+' || c == '-');
+    let signs = charge.len() - digits.len();
+    let magnitude = match (digits.is_empty(), signs) {
+        (true, n) => n as i32,
+        (false, 1) if charge.starts_with(digits) || charge.ends_with(digits) => {
+            digits.parse::<i32>().map_err(|_| invalid())?
+        }
+        _ => return Err(invalid()),
+    };
+    if magnitude == 0 {
+        return Err(invalid());
+    }
+    Ok(sign * magnitude)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+An adduct ion formed from `multimer` copies of a neutral molecule `M`, with
+`delta` added to it, carrying `charge`.
+
+`delta` includes the electrons lost or gained to reach `charge` as the `e*`
+pseudo-element, so the mass of `[M+H]+` is the mass of `M` plus a proton,
+not a hydrogen atom. Formatted, it reads back with [`parse_formula`](crate::parse_formula).
+*/
+pub struct Adduct<'lifespan> {
+    /// The bracket notation the adduct was created from
+    pub name: String,
+    /// The number of copies of the molecule in the ion
+    pub multimer: i32,
+    /// The net composition added to the molecule, including electrons
+    pub delta: ChemicalComposition<'lifespan>,
+    pub charge: i32,
+}
+
+impl<'lifespan> Adduct<'lifespan> {
+    /// Create an adduct from the atoms it adds or removes, accounting for the
+    /// electrons implied by `charge`, which fails if `periodic_table` lacks `e*`
+    pub fn new(
+        name: String,
+        multimer: i32,
+        mut delta: ChemicalComposition<'lifespan>,
+        charge: i32,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> Result<Self, AdductParsingError> {
+        let electron = periodic_table
+            .get("e*")
+            .ok_or(AdductParsingError::MissingElectron)?;
+        delta.inc(ElementSpecification::new(electron, 0), -charge);
+        Ok(Self {
+            name,
+            multimer,
+            delta,
+            charge,
+        })
+    }
+
+    /**
+    Parse an adduct in bracket notation like `[M+Na]+` or `[2M-H2O+H]+` using
+    `periodic_table`. Terms may be prefixed with a count like `2H`, and may use the
+    [built-in abbreviations](AbbreviationRegistry::builtin), which include common
+    solvents like `ACN`, `MeOH` or `FA`.
+    */
+    pub fn parse_with(
+        string: &str,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> Result<Self, AdductParsingError> {
+        if std::ptr::eq(periodic_table, &*PERIODIC_TABLE) {
+            return Self::parse_with_abbreviations(string, &ADDUCT_ABBREVIATIONS);
+        }
+        let abbreviations = AbbreviationRegistry::builtin_with_table(periodic_table);
+        Self::parse_with_abbreviations(string, &abbreviations)
+    }
+
+    /// Parse an adduct in bracket notation like [`Adduct::parse_with`], expanding the
+    /// abbreviations in `abbreviations` and using its periodic table
+    pub fn parse_with_abbreviations(
+        string: &str,
+        abbreviations: &AbbreviationRegistry<'lifespan>,
+    ) -> Result<Self, AdductParsingError> {
+        let string = string.trim();
+        let (body, charge) = match (string.strip_prefix('['), string.rfind(']')) {
+            (Some(_), Some(end)) => (&string[1..end], &string[end + 1..]),
+            _ => return Err(AdductParsingError::MissingBrackets),
+        };
+        let charge = parse_charge(charge)?;
+
+        // Split the body into signed terms, the first of which must be the molecule
+        let mut terms: Vec<(i32, &str)> = Vec::new();
+        let mut start = 0;
+        let mut sign = 1;
+        for (i, c) in body.char_indices() {
+            if c == '+' || c == '-' {
+                terms.push((sign, &body[start..i]));
+                sign = if c == '+' { 1 } else { -1 };
+                start = i + 1;
+            }
+        }
+        terms.push((sign, &body[start..]));
+
+        let mut terms = terms.into_iter();
+        let multimer = match terms.next() {
+            Some((1, molecule)) => match split_multiplier(molecule)? {
+                (n, "M") if n > 0 => n,
+                _ => return Err(AdductParsingError::MissingMolecule),
+            },
+            _ => return Err(AdductParsingError::MissingMolecule),
+        };
+
+        let mut delta = ChemicalComposition::new();
+        for (sign, term) in terms {
+            let (count, formula) = split_multiplier(term)?;
+            if formula.is_empty() {
+                return Err(AdductParsingError::InvalidTerm(term.to_string()));
+            }
+            if !formula
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '(' || c == ')')
+            {
+                return Err(AdductParsingError::InvalidTerm(term.to_string()));
+            }
+            let part = abbreviations
+                .parse(formula)
+                .map_err(|e| AdductParsingError::FormulaError(term.to_string(), Box::new(e)))?;
+            delta += &(&part * (sign * count));
+        }
+        Self::new(
+            string.to_string(),
+            multimer,
+            delta,
+            charge,
+            abbreviations.periodic_table(),
+        )
+    }
+
+    /// The composition of the ion formed from `composition`, including its electrons
+    pub fn ion_composition<
+        'inner,
+        C: ChemicalCompositionLike<'inner, 'lifespan> + Clone + Into<ChemicalComposition<'lifespan>>,
+    >(
+        &self,
+        composition: &C,
+    ) -> ChemicalComposition<'lifespan>
+    where
+        'lifespan: 'inner,
+    {
+        let molecule: ChemicalComposition<'lifespan> = composition.clone().into();
+        &(&molecule * self.multimer) + &self.delta
+    }
+
+    /// The monoisotopic mass of the ion formed from a molecule of mass `neutral_mass`
+    pub fn ion_mass(&self, neutral_mass: f64) -> f64 {
+        neutral_mass * self.multimer as f64 + self.delta.mass()
+    }
+
+    /// The m/z of the ion formed from a molecule of mass `neutral_mass`
+    pub fn mz_from_mass(&self, neutral_mass: f64) -> f64 {
+        self.ion_mass(neutral_mass) / (self.charge.abs().max(1) as f64)
+    }
+
+    /// The m/z of the ion formed from `composition`
+    pub fn mz<'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(&self, composition: &C) -> f64
+    where
+        'lifespan: 'inner,
+    {
+        self.mz_from_mass(composition.mass())
+    }
+
+    /// The neutral mass of the molecule which forms this adduct at `mz`
+    pub fn neutral_mass(&self, mz: f64) -> f64 {
+        (mz * (self.charge.abs().max(1) as f64) - self.delta.mass()) / self.multimer as f64
+    }
+}
+
+impl FromStr for Adduct<'_> {
+    type Err = AdductParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Adduct::parse_with_abbreviations(s, &ADDUCT_ABBREVIATIONS)
+    }
+}
+
+impl Display for Adduct<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// The built-in table of common positive and negative mode adducts
+pub fn common_adducts() -> &'static [Adduct<'static>] {
+    &COMMON_ADDUCTS
+}
+
+/// The common adducts with a positive charge
+pub fn positive_adducts() -> impl Iterator<Item = &'static Adduct<'static>> {
+    COMMON_ADDUCTS.iter().filter(|a| a.charge > 0)
+}
+
+/// The common adducts with a negative charge
+pub fn negative_adducts() -> impl Iterator<Item = &'static Adduct<'static>> {
+    COMMON_ADDUCTS.iter().filter(|a| a.charge < 0)
+}
+
+/// Look up a built-in adduct by its bracket notation
+pub fn find_adduct(name: &str) -> Option<&'static Adduct<'static>> {
+    COMMON_ADDUCTS.iter().find(|a| a.name == name)
+}
+
+/**
+Infer the neutral mass of the molecule which would produce an ion at `mz` under
+each of `adducts`, skipping those which would require a non-positive mass.
+*/
+pub fn infer_neutral_masses<'a, 'lifespan: 'a, I: IntoIterator<Item = &'a Adduct<'lifespan>>>(
+    mz: f64,
+    adducts: I,
+) -> Vec<(&'a Adduct<'lifespan>, f64)> {
+    adducts
+        .into_iter()
+        .map(|adduct| (adduct, adduct.neutral_mass(mz)))
+        .filter(|(_, mass)| *mass > 0.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{mass_charge_ratio, PROTON};
+
+    fn glucose() -> ChemicalComposition<'static> {
+        ChemicalComposition::parse("C6H12O6").unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let adduct: Adduct = "[2M-H2O+H]+".parse().unwrap();
+        assert_eq!(adduct.multimer, 2);
+        assert_eq!(adduct.charge, 1);
+        assert_eq!(adduct.delta.get_str("H"), -1);
+        assert_eq!(adduct.delta.get_str("O"), -1);
+        assert_eq!(adduct.delta.get_str("e*"), -1);
+        assert_eq!(adduct.to_string(), "[2M-H2O+H]+");
+
+        let adduct: Adduct = "[M+2H]2+".parse().unwrap();
+        assert_eq!(adduct.charge, 2);
+        assert_eq!(adduct.delta.get_str("H"), 2);
+        assert_eq!("[M+2H]+2".parse::<Adduct>().unwrap().charge, 2);
+        assert_eq!("[M+2H]++".parse::<Adduct>().unwrap().charge, 2);
+
+        let adduct: Adduct = "[M+ACN+H]+".parse().unwrap();
+        assert_eq!(adduct.delta.get_str("C"), 2);
+        assert_eq!(adduct.delta.get_str("N"), 1);
+
+        assert!(matches!(
+            "M+H+".parse::<Adduct>(),
+            Err(AdductParsingError::MissingBrackets)
+        ));
+        assert!(matches!(
+            "[H+M]+".parse::<Adduct>(),
+            Err(AdductParsingError::MissingMolecule)
+        ));
+        assert!(matches!(
+            "[M+H]".parse::<Adduct>(),
+            Err(AdductParsingError::InvalidCharge(_))
+        ));
+        assert!(matches!(
+            "[M+H]+-".parse::<Adduct>(),
+            Err(AdductParsingError::InvalidCharge(_))
+        ));
+        assert!(matches!(
+            "[M++H]+".parse::<Adduct>(),
+            Err(AdductParsingError::InvalidTerm(_))
+        ));
+        assert!(matches!(
+            "[99999999999M+H]+".parse::<Adduct>(),
+            Err(AdductParsingError::InvalidTerm(_))
+        ));
+        assert!(matches!(
+            "[M+99999999999H]+".parse::<Adduct>(),
+            Err(AdductParsingError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let adduct: Adduct = "[M+Hex+Na]+".parse().unwrap();
+        assert_eq!(adduct.delta.get_str("C"), 6);
+
+        let mut abbreviations = AbbreviationRegistry::builtin();
+        abbreviations.register_formula("EtOH", "EtOH").unwrap();
+        let adduct = Adduct::parse_with_abbreviations("[M+EtOH+H]+", &abbreviations).unwrap();
+        assert_eq!(adduct.delta.get_str("C"), 2);
+        assert_eq!(adduct.delta.get_str("H"), 7);
+
+        let adduct = Adduct::parse_with("[M+ACN+H]+", &PERIODIC_TABLE).unwrap();
+        assert_eq!(adduct, "[M+ACN+H]+".parse().unwrap());
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        for adduct in common_adducts() {
+            let formula = adduct.delta.to_string();
+            let delta = crate::parse_formula(&formula).unwrap();
+            assert_eq!(delta, adduct.delta, "{}", adduct.name);
+        }
+    }
+
+    #[test]
+    fn test_missing_electron() {
+        let mut table = PeriodicTable::new();
+        for symbol in ["H", "C", "O", "Na"] {
+            table.add(crate::PERIODIC_TABLE[symbol].clone());
+        }
+        assert!(matches!(
+            Adduct::parse_with("[M+Na]+", &table),
+            Err(AdductParsingError::MissingElectron)
+        ));
+    }
+
+    #[test]
+    fn test_mz() {
+        let glucose = glucose();
+        let mass = glucose.mass();
+
+        let adduct = find_adduct("[M+H]+").unwrap();
+        assert!((adduct.mz(&glucose) - mass_charge_ratio(mass, 1, PROTON)).abs() < 1e-5);
+
+        let adduct = find_adduct("[M-H]-").unwrap();
+        assert!((adduct.mz(&glucose) - mass_charge_ratio(mass, -1, PROTON)).abs() < 1e-5);
+
+        let adduct = find_adduct("[M+2H]2+").unwrap();
+        assert!((adduct.mz(&glucose) - mass_charge_ratio(mass, 2, PROTON)).abs() < 1e-5);
+
+        let adduct = find_adduct("[2M+Na]+").unwrap();
+        assert!((adduct.mz(&glucose) - (2.0 * mass + 22.989221)).abs() < 1e-5);
+        assert_eq!(adduct.ion_composition(&glucose).get_str("C"), 12);
+
+        let adduct = find_adduct("[M+Cl]-").unwrap();
+        assert!((adduct.mz(&glucose) - (mass + 34.968853 + 0.000549)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_infer_neutral_mass() {
+        let glucose = glucose();
+        let mass = glucose.mass();
+        for adduct in common_adducts() {
+            let mz = adduct.mz(&glucose);
+            assert!(
+                (adduct.neutral_mass(mz) - mass).abs() < 1e-6,
+                "{adduct} {mz}"
+            );
+        }
+
+        let mz = find_adduct("[M+Na]+").unwrap().mz(&glucose);
+        let candidates = infer_neutral_masses(mz, positive_adducts());
+        let hit = candidates
+            .iter()
+            .find(|(adduct, _)| adduct.name == "[M+Na]+")
+            .unwrap();
+        assert!((hit.1 - mass).abs() < 1e-6);
+        assert!(candidates.iter().all(|(a, _)| a.charge > 0));
+        assert!(negative_adducts().all(|a| a.charge < 0));
+    }
+}
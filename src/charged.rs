@@ -0,0 +1,330 @@
+//! Compositions of ions which carry their own charge state and charge carrier.
+use std::fmt::{self, Display};
+
+use crate::{mass_charge_ratio, ChemicalComposition, ElementSpecification, PERIODIC_TABLE};
+
+fn element(symbol: &str) -> ElementSpecification<'static> {
+    ElementSpecification::new(&PERIODIC_TABLE[symbol], 0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The reasons a [`ChargeCarrier`] or [`ChargedComposition`] can't be built
+pub enum ChargeError {
+    /// A charge carrier must carry a non-zero charge
+    UnchargedCarrier,
+    /// The charge state can't be reached with a whole number of carriers
+    IndivisibleCharge { charge: i32, carrier_charge: i32 },
+}
+
+impl Display for ChargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnchargedCarrier => write!(f, "A charge carrier cannot have a charge of 0"),
+            Self::IndivisibleCharge {
+                charge,
+                carrier_charge,
+            } => write!(
+                f,
+                "A charge of {charge} cannot be formed from carriers of charge {carrier_charge}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChargeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+The species which is added to or removed from a neutral molecule to give
+it a charge, like a proton, a sodium cation or an electron.
+
+The carrier's composition includes the electrons it lacks or carries as the
+`e*` pseudo-element, so a proton is `H` with one fewer electron.
+*/
+pub struct ChargeCarrier<'lifespan> {
+    pub composition: ChemicalComposition<'lifespan>,
+    charge: i32,
+}
+
+impl<'lifespan> ChargeCarrier<'lifespan> {
+    /// A carrier of `charge` with the given composition, which must be non-zero
+    pub fn new(
+        composition: ChemicalComposition<'lifespan>,
+        charge: i32,
+    ) -> Result<Self, ChargeError> {
+        if charge == 0 {
+            return Err(ChargeError::UnchargedCarrier);
+        }
+        Ok(Self {
+            composition,
+            charge,
+        })
+    }
+
+    /// A carrier of `charge` made from the atoms of `composition`, with the
+    /// electrons removed or added to give it that charge
+    pub fn from_atoms(
+        mut composition: ChemicalComposition<'lifespan>,
+        charge: i32,
+    ) -> Result<Self, ChargeError> {
+        composition.inc(element("e*"), -charge);
+        Self::new(composition, charge)
+    }
+
+    /// A singly charged cation made from the atoms of `composition`
+    fn cation(mut composition: ChemicalComposition<'lifespan>) -> Self {
+        composition.inc(element("e*"), -1);
+        Self {
+            composition,
+            charge: 1,
+        }
+    }
+
+    /// H+
+    pub fn proton() -> Self {
+        Self::cation(ChemicalComposition::from(vec![("H", 1)]))
+    }
+
+    /// Na+
+    pub fn sodium() -> Self {
+        Self::cation(ChemicalComposition::from(vec![("Na", 1)]))
+    }
+
+    /// K+
+    pub fn potassium() -> Self {
+        Self::cation(ChemicalComposition::from(vec![("K", 1)]))
+    }
+
+    /// NH4+
+    pub fn ammonium() -> Self {
+        Self::cation(ChemicalComposition::from(vec![("N", 1), ("H", 4)]))
+    }
+
+    /// e-
+    pub fn electron() -> Self {
+        Self {
+            composition: ChemicalComposition::from(vec![("e*", 1)]),
+            charge: -1,
+        }
+    }
+
+    /// The charge of a single carrier, e.g. `1` for H+ or `-1` for e-
+    pub fn charge(&self) -> i32 {
+        self.charge
+    }
+
+    /// The monoisotopic mass of a single carrier
+    pub fn mass(&self) -> f64 {
+        self.composition.mass()
+    }
+
+    /**
+    The number of carriers needed to give a molecule `charge`. A negative number
+    means carriers are removed, as with a deprotonated ion.
+
+    Returns [`None`] if `charge` is not a multiple of the carrier's charge.
+    */
+    pub fn count_for(&self, charge: i32) -> Option<i32> {
+        (charge % self.charge == 0).then(|| charge / self.charge)
+    }
+}
+
+impl Default for ChargeCarrier<'_> {
+    fn default() -> Self {
+        Self::proton()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/**
+A neutral [`ChemicalComposition`] paired with the charge state it was observed in
+and the [`ChargeCarrier`] which gave it that charge.
+
+```rust
+# use chemical_elements::{ChemicalComposition, ChargedComposition, ChargeCarrier};
+let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+let ion = ChargedComposition::new(glucose, 1, ChargeCarrier::sodium()).unwrap();
+assert!((ion.mz() - 203.05261).abs() < 1e-4);
+```
+*/
+pub struct ChargedComposition<'lifespan> {
+    /// The composition of the neutral molecule
+    pub composition: ChemicalComposition<'lifespan>,
+    charge: i32,
+    carrier: ChargeCarrier<'lifespan>,
+    carrier_count: i32,
+}
+
+impl<'lifespan> ChargedComposition<'lifespan> {
+    /// Charge `composition` with `carrier`, failing if `charge` is not a
+    /// multiple of the carrier's charge
+    pub fn new(
+        composition: ChemicalComposition<'lifespan>,
+        charge: i32,
+        carrier: ChargeCarrier<'lifespan>,
+    ) -> Result<Self, ChargeError> {
+        let carrier_count = carrier
+            .count_for(charge)
+            .ok_or(ChargeError::IndivisibleCharge {
+                charge,
+                carrier_charge: carrier.charge,
+            })?;
+        Ok(Self {
+            composition,
+            charge,
+            carrier,
+            carrier_count,
+        })
+    }
+
+    /// Charge `composition` by adding or removing `charge` protons
+    pub fn protonated(composition: ChemicalComposition<'lifespan>, charge: i32) -> Self {
+        Self {
+            composition,
+            charge,
+            carrier: ChargeCarrier::proton(),
+            carrier_count: charge,
+        }
+    }
+
+    pub fn charge(&self) -> i32 {
+        self.charge
+    }
+
+    pub fn carrier(&self) -> &ChargeCarrier<'lifespan> {
+        &self.carrier
+    }
+
+    /// The number of charge carriers added to the neutral molecule
+    pub fn carrier_count(&self) -> i32 {
+        self.carrier_count
+    }
+
+    /// The composition of the ion, including its charge carriers and the `e*`
+    /// electrons they lost or gained, which reads back with [`crate::parse_formula`]
+    pub fn ion_composition(&self) -> ChemicalComposition<'lifespan> {
+        &self.composition + &(&self.carrier.composition * self.carrier_count())
+    }
+
+    /// The monoisotopic mass of the neutral molecule
+    pub fn neutral_mass(&self) -> f64 {
+        self.composition.mass()
+    }
+
+    /// The monoisotopic mass of the ion, including its charge carriers
+    pub fn ion_mass(&self) -> f64 {
+        self.neutral_mass() + self.carrier.mass() * self.carrier_count() as f64
+    }
+
+    /// The monoisotopic mass-to-charge ratio of the ion. If the charge is `0`,
+    /// this is the neutral mass.
+    pub fn mz(&self) -> f64 {
+        if self.charge == 0 {
+            self.neutral_mass()
+        } else {
+            mass_charge_ratio(self.ion_mass(), self.charge, 0.0)
+        }
+    }
+
+    /**
+    Split the ion composition into the part with an isotopic distribution and
+    a charge carrier mass shift, as used by the isotopic pattern generators.
+    Pseudo-elements without natural isotopes, like `e*`, only shift the mass.
+    */
+    pub(crate) fn isotopic_parts(&self) -> (ChemicalComposition<'lifespan>, f64) {
+        let ion = self.ion_composition();
+        let mut composition = ChemicalComposition::new();
+        let mut shift = 0.0;
+        for (elt_spec, count) in ion.iter() {
+            if elt_spec.element.most_abundant_isotope == 0 && elt_spec.isotope == 0 {
+                shift += elt_spec.mass() * *count as f64;
+            } else if *count != 0 {
+                composition.set(*elt_spec, *count);
+            }
+        }
+        let carrier_shift = if self.charge == 0 {
+            0.0
+        } else {
+            shift / self.charge as f64
+        };
+        (composition, carrier_shift)
+    }
+}
+
+impl<'lifespan> From<ChemicalComposition<'lifespan>> for ChargedComposition<'lifespan> {
+    fn from(value: ChemicalComposition<'lifespan>) -> Self {
+        Self::protonated(value, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{neutral_mass, PROTON};
+
+    #[test]
+    fn test_carriers() {
+        assert!((ChargeCarrier::proton().mass() - PROTON).abs() < 1e-6);
+        assert!((ChargeCarrier::sodium().mass() - 22.989221).abs() < 1e-5);
+        assert!((ChargeCarrier::ammonium().mass() - 18.033826).abs() < 1e-5);
+        assert!((ChargeCarrier::electron().mass() - 0.000549).abs() < 1e-6);
+        assert_eq!(ChargeCarrier::electron().count_for(-2), Some(2));
+        assert_eq!(ChargeCarrier::proton().count_for(-2), Some(-2));
+        assert_eq!(
+            ChargeCarrier::proton(),
+            ChargeCarrier::from_atoms(ChemicalComposition::from(vec![("H", 1)]), 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_charge() {
+        let calcium =
+            ChargeCarrier::from_atoms(ChemicalComposition::from(vec![("Ca", 1)]), 2).unwrap();
+        assert_eq!(calcium.count_for(1), None);
+        assert_eq!(calcium.count_for(4), Some(2));
+        assert_eq!(
+            ChargeCarrier::new(ChemicalComposition::from(vec![("Na", 1)]), 0),
+            Err(ChargeError::UnchargedCarrier)
+        );
+
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        let err = ChargedComposition::new(glucose.clone(), 1, calcium.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ChargeError::IndivisibleCharge {
+                charge: 1,
+                carrier_charge: 2
+            }
+        );
+        let ion = ChargedComposition::new(glucose, 0, calcium).unwrap();
+        assert_eq!(ion.carrier_count(), 0);
+        assert_eq!(ion.mz(), ion.neutral_mass());
+    }
+
+    #[test]
+    fn test_mz() {
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        let mass = glucose.mass();
+
+        let ion = ChargedComposition::protonated(glucose.clone(), 2);
+        assert!((ion.mz() - mass_charge_ratio(mass, 2, PROTON)).abs() < 1e-6);
+        assert!((neutral_mass(ion.mz(), 2, PROTON) - ion.neutral_mass()).abs() < 1e-6);
+        assert_eq!(ion.ion_composition().get_str("H"), 14);
+        assert_eq!(ion.ion_composition().get_str("e*"), -2);
+        let formula = ion.ion_composition().to_string();
+        assert_eq!(
+            crate::parse_formula(&formula).unwrap(),
+            ion.ion_composition()
+        );
+
+        let ion = ChargedComposition::protonated(glucose.clone(), -1);
+        assert!((ion.mz() - mass_charge_ratio(mass, -1, PROTON)).abs() < 1e-6);
+        assert_eq!(ion.ion_composition().get_str("H"), 11);
+
+        let ion = ChargedComposition::new(glucose.clone(), -1, ChargeCarrier::electron()).unwrap();
+        assert!((ion.mz() - (mass + 0.000549)).abs() < 1e-6);
+
+        let ion = ChargedComposition::from(glucose);
+        assert_eq!(ion.mz(), mass);
+    }
+}
@@ -22,6 +22,7 @@ and multiplication by integers.
 pub struct ChemicalCompositionVec<'a> {
     pub composition: Vec<(ElementSpecification<'a>, i32)>,
     mass_cache: Option<f64>,
+    average_mass_cache: Option<f64>,
 }
 
 /**
@@ -80,6 +81,7 @@ impl<'lifespan> ChemicalCompositionVec<'lifespan> {
             self.composition.push((elt_spec, count));
         }
         self.mass_cache = None;
+        self.average_mass_cache = None;
     }
 
     #[inline]
@@ -90,12 +92,20 @@ impl<'lifespan> ChemicalCompositionVec<'lifespan> {
         self.set(elt_spec, i + count);
     }
 
+    /// Remove the elements whose count is zero, like those left by subtraction.
+    ///
+    /// Equality, hashing and formatting already treat these elements as absent,
+    /// but they are still counted by [`ChemicalCompositionVec::len`].
+    pub fn prune_zeros(&mut self) {
+        self.composition.retain(|(_, count)| *count != 0);
+    }
+
     #[inline]
-    pub fn iter(&self) -> Iter<(ElementSpecification<'lifespan>, i32)> {
+    pub fn iter(&self) -> Iter<'_, (ElementSpecification<'lifespan>, i32)> {
         (self.composition).iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<(ElementSpecification<'lifespan>, i32)> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, (ElementSpecification<'lifespan>, i32)> {
         self.composition.iter_mut()
     }
 
@@ -130,13 +140,7 @@ impl<'lifespan> ChemicalCompositionVec<'lifespan> {
     pub fn calc_mass(&self) -> f64 {
         let mut total = 0.0;
         for (elt_spec, count) in &self.composition {
-            let element = elt_spec.element;
-            total = if elt_spec.isotope == 0 {
-                element.most_abundant_mass
-            } else {
-                element.isotopes[&elt_spec.isotope].mass
-            }
-            .mul_add(*count as f64, total);
+            total = elt_spec.mass().mul_add(*count as f64, total);
         }
         total
     }
@@ -175,6 +179,55 @@ impl<'lifespan> ChemicalCompositionVec<'lifespan> {
     pub fn has_mass_cached(&self) -> bool {
         self.mass_cache.is_some()
     }
+
+    #[inline]
+    /**
+    Explicitly calculate the average mass, or molecular weight, of the chemical
+    composition from the natural isotopic abundances of each element, ignoring
+    any caching. Elements with a fixed isotope contribute that isotope's mass.
+    */
+    pub fn calc_average_mass(&self) -> f64 {
+        let mut total = 0.0;
+        for (elt_spec, count) in &self.composition {
+            total = elt_spec.average_mass().mul_add(*count as f64, total);
+        }
+        total
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition. If the average mass cache
+    has been populated, return that instead of repeating the calculation.
+    */
+    pub fn average_mass(&self) -> f64 {
+        match self.average_mass_cache {
+            None => self.calc_average_mass(),
+            Some(val) => val,
+        }
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition, and cache it,
+    or reuse the cached value. This requires mutability, so this method
+    must be called explicitly.
+    */
+    pub fn faverage_mass(&mut self) -> f64 {
+        match self.average_mass_cache {
+            None => {
+                let total = self.average_mass();
+                self.average_mass_cache = Some(total);
+                total
+            }
+            Some(val) => val,
+        }
+    }
+
+    #[inline]
+    /// Test if the average mass cache is populated.
+    pub fn has_average_mass_cached(&self) -> bool {
+        self.average_mass_cache.is_some()
+    }
 }
 
 const ZERO: i32 = 0;
@@ -197,6 +250,7 @@ impl<'lifespan> IndexMut<&ElementSpecification<'lifespan>> for ChemicalCompositi
     #[inline]
     fn index_mut(&mut self, key: &ElementSpecification<'lifespan>) -> &mut Self::Output {
         self.mass_cache = None;
+        self.average_mass_cache = None;
         if let Some(i) = self.find(key) {
             let (_, out) = self.composition.get_mut(i).unwrap();
             out
@@ -241,6 +295,7 @@ impl IndexMut<&str> for ChemicalCompositionVec<'_> {
     #[inline]
     fn index_mut(&mut self, key: &str) -> &mut Self::Output {
         self.mass_cache = None;
+        self.average_mass_cache = None;
         let key = key.parse::<ElementSpecification>().unwrap();
         let entry = self.index_mut(&key);
         entry
@@ -286,17 +341,6 @@ impl<'lifespan, 'transient, 'outer: 'transient> ChemicalCompositionVec<'lifespan
     }
 }
 
-impl<'lifespan> PartialEq<ChemicalCompositionVec<'lifespan>> for ChemicalCompositionVec<'lifespan> {
-    #[inline]
-    fn eq(&self, other: &ChemicalCompositionVec<'lifespan>) -> bool {
-        if self.len() != other.len() {
-            false
-        } else {
-            self.iter().all(|(k, v)| other.get(k) == *v)
-        }
-    }
-}
-
 impl<'lifespan> FromIterator<(ElementSpecification<'lifespan>, i32)>
     for ChemicalCompositionVec<'lifespan>
 {
@@ -342,6 +386,7 @@ impl<'lifespan> From<Vec<(ElementSpecification<'lifespan>, i32)>>
         ChemicalCompositionVec {
             composition: elements,
             mass_cache: None,
+            average_mass_cache: None,
         }
     }
 }
@@ -419,6 +464,23 @@ mod test {
         assert!((mass - calc).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_average_mass() {
+        let mut case = ChemicalCompositionVec::from(vec![("O", 1), ("H", 2)]);
+        let mass = 18.01528;
+
+        assert!(!case.has_average_mass_cached());
+        let calc = case.faverage_mass();
+        assert!((mass - calc).abs() < 1e-3);
+        assert!(case.has_average_mass_cached());
+        assert!(!case.has_mass_cached());
+
+        case.inc("H[2]".parse().unwrap(), 1);
+        assert!(!case.has_average_mass_cached());
+        let calc = case.average_mass();
+        assert!((mass + 2.0141017 - calc).abs() < 1e-3);
+    }
+
     #[test]
     fn test_add() {
         let case = ChemicalCompositionVec::from(vec![("O", 1), ("H", 2)]);
@@ -11,7 +11,8 @@ use fnv::FnvBuildHasher;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 use crate::element_specification::{ElementSpecification, ElementSpecificationLike};
-use crate::formula::FormulaParserError;
+use crate::formula::{FormulaParser, FormulaParserError};
+use crate::PERIODIC_TABLE;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
@@ -24,6 +25,7 @@ and multiplication by integers.
 pub struct ChemicalCompositionMap<'a> {
     pub composition: HashMap<ElementSpecification<'a>, i32, FnvBuildHasher>,
     mass_cache: Option<f64>,
+    average_mass_cache: Option<f64>,
 }
 
 /**
@@ -52,6 +54,7 @@ impl<'lifespan> ChemicalCompositionMap<'lifespan> {
     pub fn set(&mut self, elt_spec: ElementSpecification<'lifespan>, count: i32) {
         self.composition.insert(elt_spec, count);
         self.mass_cache = None;
+        self.average_mass_cache = None;
     }
 
     #[inline]
@@ -62,13 +65,21 @@ impl<'lifespan> ChemicalCompositionMap<'lifespan> {
         self.set(elt_spec, i + count);
     }
 
+    /// Remove the elements whose count is zero, like those left by subtraction.
+    ///
+    /// Equality, hashing and formatting already treat these elements as absent,
+    /// but they are still counted by [`ChemicalCompositionMap::len`].
+    pub fn prune_zeros(&mut self) {
+        self.composition.retain(|_, count| *count != 0);
+    }
+
     #[inline]
-    pub fn iter(&self) -> Iter<ElementSpecification<'lifespan>, i32> {
+    pub fn iter(&self) -> Iter<'_, ElementSpecification<'lifespan>, i32> {
         (self.composition).iter()
     }
 
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<ElementSpecification<'lifespan>, i32> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, ElementSpecification<'lifespan>, i32> {
         (self.composition).iter_mut()
     }
 
@@ -94,13 +105,7 @@ impl<'lifespan> ChemicalCompositionMap<'lifespan> {
     pub fn calc_mass(&self) -> f64 {
         let mut total = 0.0;
         for (elt_spec, count) in &self.composition {
-            let element = elt_spec.element;
-            total = if elt_spec.isotope == 0 {
-                element.most_abundant_mass
-            } else {
-                element.isotopes[&elt_spec.isotope].mass
-            }
-            .mul_add(*count as f64, total);
+            total = elt_spec.mass().mul_add(*count as f64, total);
         }
         total
     }
@@ -139,6 +144,55 @@ impl<'lifespan> ChemicalCompositionMap<'lifespan> {
     pub fn has_mass_cached(&self) -> bool {
         self.mass_cache.is_some()
     }
+
+    #[inline]
+    /**
+    Explicitly calculate the average mass, or molecular weight, of the chemical
+    composition from the natural isotopic abundances of each element, ignoring
+    any caching. Elements with a fixed isotope contribute that isotope's mass.
+    */
+    pub fn calc_average_mass(&self) -> f64 {
+        let mut total = 0.0;
+        for (elt_spec, count) in &self.composition {
+            total = elt_spec.average_mass().mul_add(*count as f64, total);
+        }
+        total
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition. If the average mass cache
+    has been populated, return that instead of repeating the calculation.
+    */
+    pub fn average_mass(&self) -> f64 {
+        match self.average_mass_cache {
+            None => self.calc_average_mass(),
+            Some(val) => val,
+        }
+    }
+
+    #[inline]
+    /**
+    Get the average mass of this chemical composition, and cache it,
+    or reuse the cached value. This requires mutability, so this method
+    must be called explicitly.
+    */
+    pub fn faverage_mass(&mut self) -> f64 {
+        match self.average_mass_cache {
+            None => {
+                let total = self.average_mass();
+                self.average_mass_cache = Some(total);
+                total
+            }
+            Some(val) => val,
+        }
+    }
+
+    #[inline]
+    /// Test if the average mass cache is populated.
+    pub fn has_average_mass_cached(&self) -> bool {
+        self.average_mass_cache.is_some()
+    }
 }
 
 impl<'lifespan, 'transient, 'outer: 'transient> ChemicalCompositionMap<'lifespan> {
@@ -192,6 +246,7 @@ impl<'lifespan> IndexMut<&ElementSpecification<'lifespan>> for ChemicalCompositi
     #[inline]
     fn index_mut(&mut self, key: &ElementSpecification<'lifespan>) -> &mut Self::Output {
         self.mass_cache = None;
+        self.average_mass_cache = None;
         let entry = self.composition.entry(*key);
         entry.or_insert(0)
     }
@@ -203,21 +258,28 @@ impl<'lifespan> IndexMut<&ElementSpecification<'lifespan>> for ChemicalCompositi
 When performing routine manipulations of a [`ChemicalCompositionMap`] it may
 be both more efficient and easier to write those operations using strings
 or string literals, rather than instantiating an [`ElementSpecification`]
-for each operation. These methods take advantage of the way
-[`HashMap::get`](std::collections::HashMap::get) is parameterized to avoid
-constructing a new [`ElementSpecification`] unless absolutely necessary.
+for each operation. These methods look the symbol up directly in the default
+[`PeriodicTable`](crate::PeriodicTable) rather than parsing an
+[`ElementSpecification`] unless absolutely necessary.
 */
 impl ChemicalCompositionMap<'_> {
+    /// The key for the natural element with the symbol `elt` in the default table
+    #[inline]
+    fn natural_key(elt: &str) -> Option<ElementSpecification<'static>> {
+        PERIODIC_TABLE
+            .get(elt)
+            .map(|element| ElementSpecification::new(element, 0))
+    }
+
     /// Get the quantity of an element by its symbol string.
     ///
     /// This method does not support fixed isotopes, but may
-    /// be faster as it skips element specification parsing and
-    /// [`PeriodicTable`](crate::PeriodicTable) lookup.
+    /// be faster as it skips element specification parsing.
     pub fn get_str(&self, elt: &str) -> i32 {
-        match self.composition.get(elt) {
-            Some(c) => *c,
-            None => 0,
-        }
+        Self::natural_key(elt)
+            .and_then(|key| self.composition.get(&key))
+            .copied()
+            .unwrap_or_default()
     }
 
     /**
@@ -225,8 +287,7 @@ impl ChemicalCompositionMap<'_> {
     if it exists. This method invalidates the mass cache.
 
     This method does not support fixed isotopes, but may
-    be faster as it skips element specification parsing and
-    [`PeriodicTable`](crate::PeriodicTable) lookup.
+    be faster as it skips element specification parsing.
 
     # Note
     While the borrow checker should stop you from mutating the object
@@ -236,15 +297,16 @@ impl ChemicalCompositionMap<'_> {
     */
     pub fn get_str_mut(&mut self, elt: &str) -> Option<&mut i32> {
         self.mass_cache = None;
-        self.composition.get_mut(elt)
+        self.average_mass_cache = None;
+        let key = Self::natural_key(elt)?;
+        self.composition.get_mut(&key)
     }
 
     /// Increment of quantity of an element by its symbol string,
     /// if it exists. This method invalidates the mass cache.
     ///
     /// This method does not support fixed isotopes, but may
-    /// be faster as it skips element specification parsing and
-    /// [`PeriodicTable`](crate::PeriodicTable) lookup, if the element is already in
+    /// be faster as it skips element specification parsing, if the element is already in
     /// the composition. Otherwise, the string is parsed and a new
     /// [`ElementSpecification`] is created using the default [`PeriodicTable`](crate::PeriodicTable).
     ///
@@ -253,6 +315,7 @@ impl ChemicalCompositionMap<'_> {
     /// this method will panic.
     pub fn inc_str(&mut self, elt: &str, count: i32) {
         self.mass_cache = None;
+        self.average_mass_cache = None;
         if let Some(val) = self.get_str_mut(elt) {
             *val += count;
         } else {
@@ -279,7 +342,9 @@ impl Index<&str> for ChemicalCompositionMap<'_> {
     #[inline]
     fn index(&self, key: &str) -> &Self::Output {
         match ElementSpecification::quick_check_str(key) {
-            ElementSpecificationLike::Yes => self.composition.get(key).unwrap_or(&ZERO),
+            ElementSpecificationLike::Yes => Self::natural_key(key)
+                .and_then(|key| self.composition.get(&key))
+                .unwrap_or(&ZERO),
             ElementSpecificationLike::No => &ZERO,
             ElementSpecificationLike::Maybe => {
                 let spec = key.parse::<ElementSpecification>();
@@ -300,19 +365,13 @@ impl IndexMut<&str> for ChemicalCompositionMap<'_> {
     #[inline]
     fn index_mut(&mut self, key: &str) -> &mut Self::Output {
         self.mass_cache = None;
+        self.average_mass_cache = None;
         let key = key.parse::<ElementSpecification>().unwrap();
         let entry = self.composition.entry(key);
         entry.or_insert(0)
     }
 }
 
-impl<'lifespan> PartialEq<ChemicalCompositionMap<'lifespan>> for ChemicalCompositionMap<'lifespan> {
-    #[inline]
-    fn eq(&self, other: &ChemicalCompositionMap<'lifespan>) -> bool {
-        self.composition == other.composition
-    }
-}
-
 impl<'lifespan> FromIterator<(ElementSpecification<'lifespan>, i32)>
     for ChemicalCompositionMap<'lifespan>
 {
@@ -363,7 +422,8 @@ impl FromStr for ChemicalCompositionMap<'_> {
     type Err = FormulaParserError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.parse()
+        let mut parser = FormulaParser::default();
+        parser.parse_formula_with_table_generic(s, &PERIODIC_TABLE)
     }
 }
 
@@ -390,6 +450,13 @@ mod test {
     //     assert_eq!(case, ctrl);
     // }
 
+    #[test]
+    fn test_from_str() {
+        let case: ChemicalCompositionMap = "H2O".parse().expect("Failed to parse");
+        let ctrl = ChemicalCompositionMap::from(vec![("O", 1), ("H", 2)]);
+        assert_eq!(case, ctrl);
+    }
+
     #[test]
     fn test_from_vec_str() {
         let case = ChemicalCompositionMap::from(vec![("O", 1), ("H", 2)]);
@@ -431,6 +498,23 @@ mod test {
         assert!((mass - calc).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_average_mass() {
+        let mut case = ChemicalCompositionMap::from(vec![("O", 1), ("H", 2)]);
+        let mass = 18.01528;
+
+        assert!(!case.has_average_mass_cached());
+        let calc = case.faverage_mass();
+        assert!((mass - calc).abs() < 1e-3);
+        assert!(case.has_average_mass_cached());
+        assert!(!case.has_mass_cached());
+
+        case.inc("H[2]".parse().unwrap(), 1);
+        assert!(!case.has_average_mass_cached());
+        let calc = case.average_mass();
+        assert!((mass + 2.0141017 - calc).abs() < 1e-3);
+    }
+
     #[test]
     fn test_add() {
         let case = ChemicalCompositionMap::from(vec![("O", 1), ("H", 2)]);
@@ -449,6 +533,16 @@ mod test {
         assert_eq!(ctrl, combo);
     }
 
+    #[test]
+    fn test_get_str_isotopes() {
+        let mut case = ChemicalCompositionMap::from(vec![("C[13]", 2), ("H", 4)]);
+        assert_eq!(case.get_str("C"), 0);
+        assert!(case.get_str_mut("C").is_none());
+        case.inc_str("C", 3);
+        assert_eq!(case.get_str("C"), 3);
+        assert_eq!(case["C[13]"], 2);
+    }
+
     #[test]
     fn test_mul() {
         let case = ChemicalCompositionMap::from(vec![("O", 1), ("H", 2)]);
@@ -0,0 +1,519 @@
+/*!
+Find the chemical compositions whose mass matches an observed mass.
+
+Element masses are discretized to integers at a fixed precision and an
+extended residue table is built over them using the round-robin algorithm
+described in [Böcker & Lipták, 2007](https://doi.org/10.1007/s00453-007-0162-8)
+and [Böcker et al., 2008](https://doi.org/10.1093/bioinformatics/btn603).
+The table is used to prune the search so that only branches which can still
+reach the target mass are explored, rather than looping over every combination
+of element counts.
+
+```rust
+# use chemical_elements::ElementSpecification;
+use chemical_elements::decomposition::{MassDecomposer, MassTolerance};
+
+let bounds = vec![
+    ("C".parse::<ElementSpecification>().unwrap(), (0, 20)),
+    ("H".parse().unwrap(), (0, 40)),
+    ("O".parse().unwrap(), (0, 10)),
+];
+let decomposer = MassDecomposer::new(bounds).unwrap();
+let hits = decomposer.decompose(180.06339, MassTolerance::PPM(5.0));
+assert_eq!(hits[0].composition.to_string(), "C6H12O6");
+```
+*/
+use std::fmt::{self, Display};
+
+use crate::{ChemicalComposition, ElementSpecification};
+
+/// The default precision, in Daltons, used to discretize element masses
+pub const DEFAULT_PRECISION: f64 = 1e-4;
+
+const UNREACHABLE: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The mass accuracy to match compositions within
+pub enum MassTolerance {
+    /// Parts-per-million of the target mass
+    PPM(f64),
+    /// An absolute error in Daltons
+    Da(f64),
+}
+
+impl MassTolerance {
+    /// The width of the tolerance window in Daltons around `mass`
+    pub fn width(&self, mass: f64) -> f64 {
+        match self {
+            Self::PPM(ppm) => mass * ppm / 1e6,
+            Self::Da(da) => *da,
+        }
+    }
+
+    /// The lower and upper bounds of the tolerance window around `mass`
+    pub fn bounds(&self, mass: f64) -> (f64, f64) {
+        let width = self.width(mass);
+        (mass - width, mass + width)
+    }
+}
+
+impl Display for MassTolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PPM(ppm) => write!(f, "{ppm}ppm"),
+            Self::Da(da) => write!(f, "{da}Da"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An error that occurred while configuring a [`MassDecomposer`]
+pub enum DecompositionError {
+    /// No elements were given to decompose over
+    NoElements,
+    /// An element's minimum count was negative or greater than its maximum
+    InvalidBounds { element: String, min: i32, max: i32 },
+    /// An element's mass is too small to be represented at the requested precision
+    MassBelowPrecision { element: String, precision: f64 },
+    /// The precision was not a positive, finite number
+    InvalidPrecision(f64),
+    /// An element was given bounds more than once
+    DuplicateElement(String),
+}
+
+impl Display for DecompositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoElements => f.write_str("No elements to decompose over"),
+            Self::InvalidBounds { element, min, max } => {
+                write!(f, "Invalid bounds [{min}, {max}] for {element}")
+            }
+            Self::MassBelowPrecision { element, precision } => write!(
+                f,
+                "The mass of {element} cannot be represented at a precision of {precision}"
+            ),
+            Self::InvalidPrecision(precision) => write!(f, "Invalid precision {precision}"),
+            Self::DuplicateElement(element) => {
+                write!(f, "{element} was given bounds more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecompositionError {}
+
+#[derive(Debug, Clone)]
+/// A composition matching the target mass of a decomposition
+pub struct Decomposition<'lifespan> {
+    pub composition: ChemicalComposition<'lifespan>,
+    /// The monoisotopic mass of [`Decomposition::composition`]
+    pub mass: f64,
+    /// The difference between [`Decomposition::mass`] and the target mass, in Daltons
+    pub error: f64,
+}
+
+impl Decomposition<'_> {
+    /// The mass error relative to the target mass, in parts-per-million
+    pub fn ppm_error(&self) -> f64 {
+        let target = self.mass - self.error;
+        self.error / target * 1e6
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecompositionElement<'lifespan> {
+    element: ElementSpecification<'lifespan>,
+    mass: f64,
+    integer_mass: u64,
+    min: u64,
+    max: u64,
+}
+
+#[derive(Debug, Clone)]
+/**
+Enumerates the compositions built from a fixed set of elements, each with
+a minimum and maximum count, whose mass falls within a tolerance of a target
+mass.
+
+Building a [`MassDecomposer`] pre-computes the extended residue table for
+its elements, so a single instance should be re-used to decompose many masses.
+*/
+pub struct MassDecomposer<'lifespan> {
+    /// Sorted by ascending mass, so the first element is the residue modulus
+    elements: Vec<DecompositionElement<'lifespan>>,
+    precision: f64,
+    /// The largest relative error introduced by discretizing any element's mass
+    max_relative_error: f64,
+    /// `residue_table[i][r]` is the smallest integer mass with residue `r` modulo
+    /// the first element's integer mass that is decomposable using the first `i + 1`
+    /// elements, or [`UNREACHABLE`]
+    residue_table: Vec<Vec<u64>>,
+    /// The mass of the minimum count of every element
+    base_mass: f64,
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<'lifespan> MassDecomposer<'lifespan> {
+    /// Create a [`MassDecomposer`] over the given elements and `(min, max)` count bounds,
+    /// discretizing masses at [`DEFAULT_PRECISION`]
+    pub fn new<I: IntoIterator<Item = (ElementSpecification<'lifespan>, (i32, i32))>>(
+        bounds: I,
+    ) -> Result<Self, DecompositionError> {
+        Self::with_precision(bounds, DEFAULT_PRECISION)
+    }
+
+    /**
+    Create a [`MassDecomposer`] over the given elements and `(min, max)` count bounds,
+    discretizing masses at `precision` Daltons.
+
+    A finer precision reduces the number of candidates which must be checked against the
+    exact mass, but the residue table grows in proportion to the lightest element's mass
+    divided by `precision`.
+    */
+    pub fn with_precision<I: IntoIterator<Item = (ElementSpecification<'lifespan>, (i32, i32))>>(
+        bounds: I,
+        precision: f64,
+    ) -> Result<Self, DecompositionError> {
+        if !(precision.is_finite() && precision > 0.0) {
+            return Err(DecompositionError::InvalidPrecision(precision));
+        }
+        let mut elements = Vec::new();
+        for (element, (min, max)) in bounds {
+            if elements
+                .iter()
+                .any(|e: &DecompositionElement| e.element == element)
+            {
+                return Err(DecompositionError::DuplicateElement(element.to_string()));
+            }
+            if min < 0 || min > max {
+                return Err(DecompositionError::InvalidBounds {
+                    element: element.to_string(),
+                    min,
+                    max,
+                });
+            }
+            let mass = element.mass();
+            let integer_mass = (mass / precision).round();
+            if integer_mass < 1.0 {
+                return Err(DecompositionError::MassBelowPrecision {
+                    element: element.to_string(),
+                    precision,
+                });
+            }
+            elements.push(DecompositionElement {
+                element,
+                mass,
+                integer_mass: integer_mass as u64,
+                min: min as u64,
+                max: max as u64,
+            });
+        }
+        if elements.is_empty() {
+            return Err(DecompositionError::NoElements);
+        }
+        elements.sort_by_key(|e| e.integer_mass);
+
+        let max_relative_error = elements
+            .iter()
+            .map(|e| ((e.integer_mass as f64 * precision - e.mass) / e.mass).abs())
+            .fold(0.0, f64::max);
+        let base_mass = elements.iter().map(|e| e.mass * e.min as f64).sum();
+
+        let mut decomposer = Self {
+            elements,
+            precision,
+            max_relative_error,
+            residue_table: Vec::new(),
+            base_mass,
+        };
+        decomposer.build_residue_table();
+        Ok(decomposer)
+    }
+
+    /// Build the extended residue table using the round-robin algorithm
+    fn build_residue_table(&mut self) {
+        let modulus = self.elements[0].integer_mass;
+        let size = modulus as usize;
+        let mut current = vec![UNREACHABLE; size];
+        current[0] = 0;
+        self.residue_table.push(current.clone());
+
+        for elt in self.elements.iter().skip(1) {
+            let step = elt.integer_mass;
+            let d = gcd(modulus, step);
+            for p in 0..d {
+                let mut n = (p..modulus)
+                    .step_by(d as usize)
+                    .map(|q| current[q as usize])
+                    .min()
+                    .unwrap_or(UNREACHABLE);
+                if n == UNREACHABLE {
+                    continue;
+                }
+                for _ in 0..(modulus / d) {
+                    n += step;
+                    let r = (n % modulus) as usize;
+                    n = n.min(current[r]);
+                    current[r] = n;
+                }
+            }
+            self.residue_table.push(current.clone());
+        }
+    }
+
+    /// The precision element masses were discretized at
+    pub fn precision(&self) -> f64 {
+        self.precision
+    }
+
+    /// The elements being decomposed over, with their `(min, max)` bounds
+    pub fn elements(
+        &self,
+    ) -> impl Iterator<Item = (ElementSpecification<'lifespan>, (i32, i32))> + '_ {
+        self.elements
+            .iter()
+            .map(|e| (e.element, (e.min as i32, e.max as i32)))
+    }
+
+    /**
+    Find all compositions within `tolerance` of `mass`, sorted by ascending
+    absolute mass error.
+    */
+    pub fn decompose(&self, mass: f64, tolerance: MassTolerance) -> Vec<Decomposition<'lifespan>> {
+        let (lower, upper) = tolerance.bounds(mass);
+        // Search for the remainder after the minimum count of each element is accounted for
+        let lower_rem = lower - self.base_mass;
+        let upper_rem = upper - self.base_mass;
+        if upper_rem < 0.0 {
+            return Vec::new();
+        }
+        let slack = upper_rem * self.max_relative_error;
+        let start = ((lower_rem - slack) / self.precision).floor().max(0.0) as u64;
+        let end = ((upper_rem + slack) / self.precision).ceil() as u64;
+
+        let mut counts = vec![0u64; self.elements.len()];
+        let mut results = Vec::new();
+        for target in start..=end {
+            self.find_decompositions(
+                self.elements.len() - 1,
+                target,
+                &mut counts,
+                &mut |counts| {
+                    let total: f64 = self
+                        .elements
+                        .iter()
+                        .zip(counts)
+                        .map(|(e, c)| e.mass * (e.min + c) as f64)
+                        .sum();
+                    if total >= lower && total <= upper {
+                        let mut composition = ChemicalComposition::new();
+                        for (e, c) in self.elements.iter().zip(counts) {
+                            if e.min + c > 0 {
+                                composition.set(e.element, (e.min + c) as i32);
+                            }
+                        }
+                        results.push(Decomposition {
+                            composition,
+                            mass: total,
+                            error: total - mass,
+                        });
+                    }
+                },
+            );
+        }
+        results.sort_by(|a, b| a.error.abs().total_cmp(&b.error.abs()));
+        results
+    }
+
+    /// Recursively enumerate the decompositions of the integer mass `target` using
+    /// the first `i + 1` elements, calling `emit` on each complete set of counts.
+    /// Counts are relative to each element's minimum.
+    fn find_decompositions<F: FnMut(&[u64])>(
+        &self,
+        i: usize,
+        target: u64,
+        counts: &mut Vec<u64>,
+        emit: &mut F,
+    ) {
+        let modulus = self.elements[0].integer_mass;
+        if i == 0 {
+            if target.is_multiple_of(modulus) {
+                let count = target / modulus;
+                if count <= self.elements[0].max - self.elements[0].min {
+                    counts[0] = count;
+                    emit(counts);
+                }
+            }
+            return;
+        }
+        let elt = &self.elements[i];
+        let limit = elt.max - elt.min;
+        let step = elt.integer_mass;
+        let lcm = modulus / gcd(modulus, step) * step;
+        let period = lcm / step;
+        let table = &self.residue_table[i - 1];
+        for j in 0..period.min(limit + 1) {
+            let used = j * step;
+            if used > target {
+                break;
+            }
+            let mut remainder = target - used;
+            let lower_bound = table[(remainder % modulus) as usize];
+            if lower_bound == UNREACHABLE {
+                continue;
+            }
+            let mut count = j;
+            while remainder >= lower_bound && count <= limit {
+                counts[i] = count;
+                self.find_decompositions(i - 1, remainder, counts, emit);
+                if remainder < lcm {
+                    break;
+                }
+                remainder -= lcm;
+                count += period;
+            }
+        }
+        counts[i] = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(s: &str) -> ElementSpecification<'static> {
+        s.parse().unwrap()
+    }
+
+    fn chnops<'a>() -> Vec<(ElementSpecification<'a>, (i32, i32))> {
+        vec![
+            (spec("C"), (0, 40)),
+            (spec("H"), (0, 80)),
+            (spec("N"), (0, 10)),
+            (spec("O"), (0, 20)),
+            (spec("P"), (0, 2)),
+            (spec("S"), (0, 2)),
+        ]
+    }
+
+    /// Enumerate every combination of counts, for comparison
+    fn brute_force(
+        bounds: &[(ElementSpecification<'static>, (i32, i32))],
+        lower: f64,
+        upper: f64,
+    ) -> usize {
+        fn step(
+            bounds: &[(ElementSpecification<'static>, (i32, i32))],
+            acc: f64,
+            lower: f64,
+            upper: f64,
+        ) -> usize {
+            match bounds.split_first() {
+                None => (acc >= lower && acc <= upper) as usize,
+                Some(((e, (min, max)), rest)) => {
+                    let mut n = 0;
+                    for c in *min..=*max {
+                        let m = acc + e.mass() * c as f64;
+                        if m > upper {
+                            break;
+                        }
+                        n += step(rest, m, lower, upper);
+                    }
+                    n
+                }
+            }
+        }
+        step(bounds, 0.0, lower, upper)
+    }
+
+    #[test]
+    fn test_decompose_glucose() {
+        let decomposer = MassDecomposer::new(chnops()).unwrap();
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        let hits = decomposer.decompose(glucose.mass(), MassTolerance::PPM(10.0));
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].composition, glucose);
+        assert!(hits[0].error.abs() < 1e-9);
+        for pair in hits.windows(2) {
+            assert!(pair[0].error.abs() <= pair[1].error.abs());
+        }
+        for hit in hits.iter() {
+            assert!(hit.ppm_error().abs() <= 10.0);
+            assert!((hit.composition.mass() - hit.mass).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let bounds = vec![
+            (spec("C"), (0, 30)),
+            (spec("H"), (0, 60)),
+            (spec("N"), (0, 6)),
+            (spec("O"), (0, 12)),
+            (spec("S"), (0, 2)),
+        ];
+        let decomposer = MassDecomposer::new(bounds.clone()).unwrap();
+        for (mass, tol) in [
+            (346.1387, MassTolerance::PPM(20.0)),
+            (229.0, MassTolerance::Da(0.05)),
+            (501.2, MassTolerance::Da(0.01)),
+        ] {
+            let (lower, upper) = tol.bounds(mass);
+            let hits = decomposer.decompose(mass, tol);
+            assert!(!hits.is_empty());
+            assert_eq!(
+                hits.len(),
+                brute_force(&bounds, lower, upper),
+                "{mass} {tol}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = vec![
+            (spec("C"), (6, 6)),
+            (spec("H"), (0, 20)),
+            (spec("O"), (0, 10)),
+            (spec("C[13]"), (0, 2)),
+        ];
+        let decomposer = MassDecomposer::new(bounds).unwrap();
+        let target = ChemicalComposition::parse("C6H12O6").unwrap().mass() + spec("C[13]").mass();
+        let hits = decomposer.decompose(target, MassTolerance::PPM(5.0));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].composition.get(&spec("C")), 6);
+        assert_eq!(hits[0].composition.get(&spec("C[13]")), 1);
+
+        assert!(decomposer
+            .decompose(50.0, MassTolerance::Da(0.5))
+            .is_empty());
+
+        assert!(matches!(
+            MassDecomposer::new(vec![(spec("C"), (3, 1))]),
+            Err(DecompositionError::InvalidBounds { .. })
+        ));
+        assert!(matches!(
+            MassDecomposer::new(vec![]),
+            Err(DecompositionError::NoElements)
+        ));
+        assert!(matches!(
+            MassDecomposer::with_precision(vec![(spec("C"), (0, 1))], 0.0),
+            Err(DecompositionError::InvalidPrecision(_))
+        ));
+        let duplicated = vec![
+            (spec("C"), (0, 1)),
+            (spec("H"), (0, 4)),
+            (spec("C"), (2, 3)),
+        ];
+        assert!(matches!(
+            MassDecomposer::new(duplicated),
+            Err(DecompositionError::DuplicateElement(element)) if element == "C"
+        ));
+    }
+}
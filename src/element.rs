@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use fnv::FnvBuildHasher as RandomState;
 
 type NeutronShiftType = i8;
-type ElementNumberType = u8;
+pub(crate) type ElementNumberType = u8;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -59,6 +59,78 @@ impl cmp::PartialOrd<Isotope> for Isotope {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/** The IUPAC standard atomic weight of an element, as reported by NIST.
+
+Elements whose isotopic composition varies appreciably between natural sources,
+like hydrogen and carbon, are given as an interval rather than a single value, and
+elements without stable isotopes only report the mass number of their longest-lived
+isotope.
+*/
+pub enum StandardAtomicWeight {
+    /// No standard atomic weight is known
+    #[default]
+    Unknown,
+    /// A conventional value with its standard uncertainty
+    Value { value: f64, uncertainty: f64 },
+    /// The bounds of the interval the atomic weight of a normal material falls in
+    Interval { lower: f64, upper: f64 },
+    /// The mass number of the longest-lived isotope of an element with no stable isotopes
+    MassNumber(u16),
+}
+
+impl StandardAtomicWeight {
+    /**
+    Parse the notation used by NIST, e.g. `12.0107(8)`, `[1.00784,1.00811]` or `[98]`.
+    An empty string is [`StandardAtomicWeight::Unknown`].
+    */
+    pub fn from_nist(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Some(Self::Unknown);
+        }
+        if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return match inner.split_once(',') {
+                Some((lower, upper)) => Some(Self::Interval {
+                    lower: lower.trim().parse().ok()?,
+                    upper: upper.trim().parse().ok()?,
+                }),
+                None => Some(Self::MassNumber(inner.trim().parse().ok()?)),
+            };
+        }
+        let (digits, uncertainty) = match value.split_once('(') {
+            Some((digits, rest)) => (digits, rest.strip_suffix(')')?),
+            None => (value, "0"),
+        };
+        let decimals = digits.split_once('.').map(|(_, d)| d.len()).unwrap_or(0);
+        let uncertainty: f64 = uncertainty.parse().ok()?;
+        Some(Self::Value {
+            value: digits.parse().ok()?,
+            uncertainty: uncertainty / 10f64.powi(decimals as i32),
+        })
+    }
+
+    /// A single representative weight, taking the midpoint of an interval
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            Self::Unknown => None,
+            Self::Value { value, .. } => Some(*value),
+            Self::Interval { lower, upper } => Some((lower + upper) / 2.0),
+            Self::MassNumber(n) => Some(*n as f64),
+        }
+    }
+
+    /// The lower and upper bounds on the weight, when they are known
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        match self {
+            Self::Value { value, uncertainty } => Some((value - uncertainty, value + uncertainty)),
+            Self::Interval { lower, upper } => Some((*lower, *upper)),
+            Self::Unknown | Self::MassNumber(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /** A chemical element with known masses and isotopic frequency.
@@ -73,7 +145,16 @@ pub struct Element {
     pub most_abundant_mass: f64,
     pub min_neutron_shift: NeutronShiftType,
     pub max_neutron_shift: NeutronShiftType,
+    /// The atomic number, Z, of the element, or `0` for pseudo-elements like `e*`
     pub element_number: ElementNumberType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub standard_atomic_weight: StandardAtomicWeight,
+    /// Whether the isotopic abundances were set artificially, as for an isotopic label,
+    /// rather than occurring naturally. See [`Element::enriched`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub labeled: bool,
 }
 
 impl Element {
@@ -81,6 +162,21 @@ impl Element {
         self.isotopes[&self.most_abundant_isotope].mass
     }
 
+    /// The abundance-weighted mean mass of this element's isotopes
+    pub fn average_mass(&self) -> f64 {
+        let (weighted, total) = self
+            .isotopes
+            .values()
+            .fold((0.0, 0.0), |(weighted, total), iso| {
+                (iso.mass.mul_add(iso.abundance, weighted), total + iso.abundance)
+            });
+        if total > 0.0 {
+            weighted / total
+        } else {
+            self.most_abundant_mass
+        }
+    }
+
     pub fn calc_min_neutron_shift(&self) -> NeutronShiftType {
         if self.min_neutron_shift != 0 {
             return self.min_neutron_shift;
@@ -114,6 +210,91 @@ impl Element {
         self.max_neutron_shift = self.calc_max_neutron_shift();
         self.min_neutron_shift = self.calc_min_neutron_shift();
     }
+
+    /**
+    Derive an isotopically labeled form of this element where `isotope` makes up
+    `enrichment` of the atoms and the other isotopes share the remainder in their
+    natural proportions, e.g. 13C at 99% enrichment.
+
+    The derived element is called `symbol`, which must be distinct from the symbols
+    of the elements it will be combined with, and must be [added](PeriodicTable::add)
+    to a [`PeriodicTable`] to be used in formulae. Returns `None` if `isotope` is not
+    a known isotope of this element or `enrichment` is not between 0 and 1.
+    */
+    pub fn enriched(&self, symbol: &str, isotope: u16, enrichment: f64) -> Option<Element> {
+        if !(0.0..=1.0).contains(&enrichment) || !self.isotopes.contains_key(&isotope) {
+            return None;
+        }
+        let rest: f64 = self
+            .isotopes
+            .iter()
+            .filter(|(k, _)| **k != isotope)
+            .map(|(_, iso)| iso.abundance)
+            .sum();
+        let abundances: Vec<(u16, f64)> = self
+            .isotopes
+            .iter()
+            .map(|(k, iso)| {
+                if *k == isotope {
+                    (*k, enrichment)
+                } else if rest > 0.0 {
+                    (*k, (1.0 - enrichment) * iso.abundance / rest)
+                } else {
+                    (*k, 0.0)
+                }
+            })
+            .collect();
+        self.with_abundances(symbol, &abundances)
+    }
+
+    /**
+    Derive an isotopically labeled form of this element with explicit isotopic abundances,
+    which are normalized to sum to 1. Isotopes which are not listed are left out.
+
+    The most abundant isotope of the derived element determines its monoisotopic
+    mass. Returns `None` if any isotope is not a known isotope of this element, any
+    abundance is negative, or no abundance is positive.
+    */
+    pub fn with_abundances(&self, symbol: &str, abundances: &[(u16, f64)]) -> Option<Element> {
+        let total: f64 = abundances.iter().map(|(_, a)| *a).sum();
+        if total <= 0.0
+            || abundances
+                .iter()
+                .any(|(k, a)| *a < 0.0 || !self.isotopes.contains_key(k))
+        {
+            return None;
+        }
+        let (most_abundant_isotope, _) = abundances
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let mut elt = Element {
+            symbol: symbol.to_string(),
+            most_abundant_isotope,
+            most_abundant_mass: self.isotopes[&most_abundant_isotope].mass,
+            element_number: self.element_number,
+            name: self.name.clone(),
+            labeled: true,
+            ..Default::default()
+        };
+        for (k, abundance) in abundances.iter().copied() {
+            if abundance == 0.0 {
+                continue;
+            }
+            let iso = &self.isotopes[&k];
+            elt.isotopes.insert(
+                k,
+                Isotope {
+                    mass: iso.mass,
+                    abundance: abundance / total,
+                    neutrons: iso.neutrons,
+                    neutron_shift: (k as i16 - most_abundant_isotope as i16) as NeutronShiftType,
+                },
+            );
+        }
+        elt.index_isotopes();
+        Some(elt)
+    }
 }
 
 impl fmt::Display for Element {
@@ -173,6 +354,67 @@ impl PeriodicTable {
     pub fn get(&self, symbol: &str) -> Option<&Element> {
         self.elements.get(symbol)
     }
+
+    /**
+    Look up an element by its atomic number.
+
+    Pseudo-elements like `H+` and `e*` have no atomic number and cannot be
+    found this way, and neither can [labeled](Element::labeled) elements. If
+    several symbols share an atomic number, as with the placeholder names for
+    the superheavy elements, the shortest one wins.
+    */
+    pub fn by_number(&self, element_number: ElementNumberType) -> Option<&Element> {
+        if element_number == 0 {
+            return None;
+        }
+        self.elements
+            .values()
+            .filter(|elt| elt.element_number == element_number && !elt.labeled)
+            .min_by(|a, b| {
+                a.symbol
+                    .len()
+                    .cmp(&b.symbol.len())
+                    .then_with(|| a.symbol.cmp(&b.symbol))
+            })
+    }
+
+    /**
+    Find the element symbol nearest to `symbol`, for suggesting corrections to
+    misspelled symbols. A case-insensitive match is preferred, followed by the
+    symbol with the fewest edits, up to two. Pseudo-elements are not suggested.
+    */
+    pub fn nearest_symbol(&self, symbol: &str) -> Option<&str> {
+        let query = symbol.to_ascii_lowercase();
+        let first = query.chars().next();
+        self.elements
+            .values()
+            .filter(|elt| elt.element_number != 0)
+            .map(|elt| {
+                let candidate = elt.symbol.to_ascii_lowercase();
+                let distance = edit_distance(&query, &candidate);
+                let same_start = candidate.chars().next() == first;
+                (distance, !same_start, elt.symbol.len(), elt.symbol.as_str())
+            })
+            .filter(|(distance, _, _, _)| *distance <= 2)
+            .min()
+            .map(|(_, _, _, symbol)| symbol)
+    }
+}
+
+/// The Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
 }
 
 impl ops::Index<&str> for PeriodicTable {
@@ -183,3 +425,114 @@ impl ops::Index<&str> for PeriodicTable {
         &self.elements[i]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PERIODIC_TABLE;
+
+    #[test]
+    fn test_standard_atomic_weight() {
+        assert_eq!(
+            StandardAtomicWeight::from_nist("[1.00784,1.00811]"),
+            Some(StandardAtomicWeight::Interval {
+                lower: 1.00784,
+                upper: 1.00811
+            })
+        );
+        assert_eq!(
+            StandardAtomicWeight::from_nist("[98]"),
+            Some(StandardAtomicWeight::MassNumber(98))
+        );
+        assert_eq!(
+            StandardAtomicWeight::from_nist(""),
+            Some(StandardAtomicWeight::Unknown)
+        );
+        assert_eq!(StandardAtomicWeight::from_nist("12.0(a)"), None);
+
+        let silver = StandardAtomicWeight::from_nist("107.8682(2)").unwrap();
+        let (lower, upper) = silver.bounds().unwrap();
+        assert!((lower - 107.8680).abs() < 1e-9);
+        assert!((upper - 107.8684).abs() < 1e-9);
+
+        let (lower, upper) = PERIODIC_TABLE["C"].standard_atomic_weight.bounds().unwrap();
+        assert_eq!((lower, upper), (12.0096, 12.0116));
+        assert!(
+            (PERIODIC_TABLE["C"].standard_atomic_weight.value().unwrap() - 12.0106).abs() < 1e-9
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_without_new_fields() {
+        let mut value = serde_json::to_value(&PERIODIC_TABLE["C"]).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for field in ["name", "standard_atomic_weight", "labeled"] {
+            fields.remove(field);
+        }
+        let carbon: Element = serde_json::from_value(value).unwrap();
+        assert_eq!(carbon, PERIODIC_TABLE["C"]);
+        assert_eq!(carbon.name, "");
+        assert_eq!(carbon.standard_atomic_weight, StandardAtomicWeight::Unknown);
+    }
+
+    #[test]
+    fn test_nearest_symbol() {
+        assert_eq!(PERIODIC_TABLE.nearest_symbol("CL"), Some("Cl"));
+        assert_eq!(PERIODIC_TABLE.nearest_symbol("na"), Some("Na"));
+        assert_eq!(PERIODIC_TABLE.nearest_symbol("Xy"), Some("Xe"));
+        assert_eq!(PERIODIC_TABLE.nearest_symbol("Qwerty"), None);
+    }
+
+    #[test]
+    fn test_by_number() {
+        let silver = PERIODIC_TABLE.by_number(47).unwrap();
+        assert_eq!(silver.symbol, "Ag");
+        assert_eq!(silver.name, "Silver");
+        assert_eq!(PERIODIC_TABLE.by_number(89).unwrap().symbol, "Ac");
+        assert_eq!(PERIODIC_TABLE.by_number(118).unwrap().symbol, "Uuo");
+        assert!(PERIODIC_TABLE.by_number(0).is_none());
+        assert!(PERIODIC_TABLE.by_number(119).is_none());
+        for (z, elt) in PERIODIC_TABLE
+            .elements
+            .values()
+            .filter(|e| e.element_number > 0)
+            .map(|e| (e.element_number, e))
+        {
+            assert_eq!(PERIODIC_TABLE.by_number(z).unwrap(), elt);
+        }
+    }
+
+    #[test]
+    fn test_enriched() {
+        let nitrogen = &PERIODIC_TABLE["N"];
+        let label = nitrogen.enriched("Nx", 15, 0.985).unwrap();
+        assert!(label.labeled);
+        assert_eq!(label.most_abundant_isotope, 15);
+        assert!((label.mass() - nitrogen.isotopes[&15].mass).abs() < 1e-9);
+        assert!((label.isotopes[&15].abundance - 0.985).abs() < 1e-9);
+        assert!((label.isotopes[&14].abundance - 0.015).abs() < 1e-9);
+        assert_eq!(label.isotopes[&14].neutron_shift, -1);
+        assert_eq!((label.min_neutron_shift, label.max_neutron_shift), (-1, 0));
+
+        // Low enrichment leaves the natural isotope most abundant
+        let label = PERIODIC_TABLE["C"].enriched("Cx", 13, 0.2).unwrap();
+        assert_eq!(label.most_abundant_isotope, 12);
+        assert!((label.average_mass() - (12.0 * 0.8 + 13.003355 * 0.2)).abs() < 1e-6);
+
+        assert!(nitrogen.enriched("Nx", 16, 0.9).is_none());
+        assert!(nitrogen.enriched("Nx", 15, 1.5).is_none());
+
+        let label = PERIODIC_TABLE["O"]
+            .with_abundances("Ox", &[(16, 1.0), (18, 3.0)])
+            .unwrap();
+        assert_eq!(label.most_abundant_isotope, 18);
+        assert_eq!(label.isotopes.len(), 2);
+        assert!((label.isotopes[&16].abundance - 0.25).abs() < 1e-9);
+        assert!(PERIODIC_TABLE["O"].with_abundances("Ox", &[]).is_none());
+
+        let mut table = PERIODIC_TABLE.clone();
+        table.add(label);
+        assert_eq!(table.by_number(8).unwrap().symbol, "O");
+    }
+}
@@ -1,21 +1,55 @@
-use std::borrow::Borrow;
 use std::cmp;
 use std::fmt::{self, Display};
 use std::hash;
 use std::str::FromStr;
 
-use crate::element::{Element, PeriodicTable};
+use crate::element::{Element, ElementNumberType, PeriodicTable};
 use crate::table::PERIODIC_TABLE;
 
-#[derive(Debug, Clone, Copy)]
+/// The mass difference between consecutive isotopes, used to approximate
+/// the mass of isotopes missing from the periodic table
+const NEUTRON_SHIFT: f64 = 1.0033548378;
+
+/**
+Look up an element symbol in `periodic_table`, also accepting the isotope symbols
+`D` and `T` for deuterium and tritium, which are returned as hydrogen with that
+isotope fixed.
+*/
+pub(crate) fn resolve_symbol<'lifespan>(
+    symbol: &str,
+    periodic_table: &'lifespan PeriodicTable,
+) -> Option<(&'lifespan Element, u16)> {
+    match periodic_table.get(symbol) {
+        Some(element) => Some((element, 0)),
+        None => match symbol {
+            "D" => periodic_table.get("H").map(|element| (element, 2)),
+            "T" => periodic_table.get("H").map(|element| (element, 3)),
+            _ => None,
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElementSpecificationParsingError {
+    /// An isotope was opened with `[` but not closed with `]`
     UnclosedIsotope,
     UnknownElement,
+    /// The isotope was not a number, or text followed it
+    MalformedIsotope,
+    /// The element has no isotope with this mass number
+    UnknownIsotope(u16),
 }
 
 impl Display for ElementSpecificationParsingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::UnclosedIsotope => f.write_str("Isotope is missing its closing ']'"),
+            Self::UnknownElement => f.write_str("Unknown element"),
+            Self::MalformedIsotope => f.write_str("Isotope must be a number enclosed in []"),
+            Self::UnknownIsotope(isotope) => {
+                write!(f, "The element has no isotope {isotope}")
+            }
+        }
     }
 }
 
@@ -59,10 +93,7 @@ pub struct ElementSpecification<'element> {
 impl cmp::PartialEq for ElementSpecification<'_> {
     #[inline]
     fn eq(&self, other: &ElementSpecification) -> bool {
-        if self.element != other.element {
-            return false;
-        }
-        self.isotope == other.isotope
+        self.ordering_key() == other.ordering_key()
     }
 }
 
@@ -79,12 +110,22 @@ impl hash::Hash for ElementSpecification<'_> {
     #[inline]
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.element.hash(state);
+        self.isotope.hash(state);
+    }
+}
+
+impl cmp::PartialOrd for ElementSpecification<'_> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl Borrow<str> for ElementSpecification<'_> {
-    fn borrow(&self) -> &str {
-        &self.element.symbol
+/// Element specifications are ordered by atomic number, and then by isotope
+impl cmp::Ord for ElementSpecification<'_> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.ordering_key().cmp(&other.ordering_key())
     }
 }
 
@@ -103,6 +144,71 @@ impl<'transient, 'lifespan: 'transient, 'element> ElementSpecification<'element>
         ElementSpecification { element, isotope }
     }
 
+    /// The fields compared by both [`PartialEq`] and [`Ord`], so the two always agree
+    #[inline]
+    fn ordering_key(&self) -> (ElementNumberType, &str, u16, u16) {
+        (
+            self.element.element_number,
+            &self.element.symbol,
+            self.isotope,
+            self.element.most_abundant_isotope,
+        )
+    }
+
+    /// Whether the specified isotope is listed for the element. Unfixed isotopes
+    /// are always known.
+    #[inline]
+    pub fn is_known_isotope(&self) -> bool {
+        self.isotope == 0 || self.element.isotopes.contains_key(&self.isotope)
+    }
+
+    /// The mass of the specified isotope, which is approximated from the most
+    /// abundant isotope by neutron shifts for synthetic isotopes not listed for
+    /// the element
+    #[inline]
+    fn isotope_mass(&self) -> f64 {
+        match self.element.isotopes.get(&self.isotope) {
+            Some(isotope) => isotope.mass,
+            None => {
+                let shift = self.isotope as f64 - self.element.most_abundant_isotope as f64;
+                shift.mul_add(NEUTRON_SHIFT, self.element.most_abundant_mass)
+            }
+        }
+    }
+
+    /// The mass of the specified isotope, or the monoisotopic mass of the element
+    /// if no isotope is fixed
+    #[inline]
+    pub fn mass(&self) -> f64 {
+        if self.isotope == 0 {
+            self.element.most_abundant_mass
+        } else {
+            self.isotope_mass()
+        }
+    }
+
+    /// The mass number of the specified isotope, or of the most abundant isotope
+    /// if no isotope is fixed. Pseudo-elements without isotopes use their rounded mass.
+    #[inline]
+    pub fn nominal_mass(&self) -> u16 {
+        match (self.isotope, self.element.most_abundant_isotope) {
+            (0, 0) => self.element.most_abundant_mass.round() as u16,
+            (0, n) => n,
+            (n, _) => n,
+        }
+    }
+
+    /// The mass of the specified isotope, or the average mass of the element
+    /// if no isotope is fixed
+    #[inline]
+    pub fn average_mass(&self) -> f64 {
+        if self.isotope == 0 {
+            self.element.average_mass()
+        } else {
+            self.isotope_mass()
+        }
+    }
+
     #[inline]
     pub fn parse(
         string: &'transient str,
@@ -141,39 +247,103 @@ impl<'transient, 'lifespan: 'transient, 'element> ElementSpecification<'element>
         }
     }
 
+    /**
+    Parse an element specification, requiring that any isotope is listed for the
+    element in `periodic_table`. The isotope may be written after the symbol like
+    `C[13]`, or before it like `[13C]`, `^13C` or `13C`, and `D` and `T` are read
+    as `H[2]` and `H[3]`.
+    */
     #[inline]
     pub fn parse_with(
         string: &'transient str,
         periodic_table: &'lifespan PeriodicTable,
     ) -> Result<ElementSpecification<'lifespan>, ElementSpecificationParsingError> {
-        let n = string.len();
-        let elt_start = 0;
-        let mut elt_end = n;
-        let mut iso_start = n;
-        let mut iso_end = n;
-        for (i, c) in string.chars().enumerate() {
-            if c == '[' {
-                elt_end = i;
-                if n > i {
-                    iso_start = i + 1;
-                } else {
-                    return Err(ElementSpecificationParsingError::UnclosedIsotope);
-                }
-            } else if c == ']' {
-                iso_end = i;
-            }
+        Self::parse_impl(string, periodic_table, false)
+    }
+
+    /// Parse an element specification, permitting isotopes which are not listed
+    /// for the element, like synthetic labels
+    #[inline]
+    pub fn parse_lenient(
+        string: &'transient str,
+    ) -> Result<ElementSpecification<'lifespan>, ElementSpecificationParsingError> {
+        Self::parse_lenient_with(string, &PERIODIC_TABLE)
+    }
+
+    #[inline]
+    pub fn parse_lenient_with(
+        string: &'transient str,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> Result<ElementSpecification<'lifespan>, ElementSpecificationParsingError> {
+        Self::parse_impl(string, periodic_table, true)
+    }
+
+    /// Split a prefixed isotope like the `13C` of `^13C` into its symbol and isotope
+    fn split_prefix_isotope(string: &str) -> Result<(&str, u16), ElementSpecificationParsingError> {
+        let digits = string.len()
+            - string
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 || digits == string.len() {
+            return Err(ElementSpecificationParsingError::MalformedIsotope);
         }
-        let elt_sym = &string[elt_start..elt_end];
-        if let Some(element) = periodic_table.get(elt_sym) {
-            let isotope = if iso_start != iso_end {
-                string[iso_start..iso_end].parse::<u16>().unwrap()
-            } else {
-                0
-            };
-            Ok(ElementSpecification::new(element, isotope))
+        let isotope = string[..digits]
+            .parse::<u16>()
+            .map_err(|_| ElementSpecificationParsingError::MalformedIsotope)?;
+        Ok((&string[digits..], isotope))
+    }
+
+    fn parse_impl(
+        string: &'transient str,
+        periodic_table: &'lifespan PeriodicTable,
+        allow_unknown_isotopes: bool,
+    ) -> Result<ElementSpecification<'lifespan>, ElementSpecificationParsingError> {
+        let (elt_sym, isotope) = if let Some(rest) = string.strip_prefix('^') {
+            Self::split_prefix_isotope(rest)?
+        } else if string.starts_with(|c: char| c.is_ascii_digit()) {
+            Self::split_prefix_isotope(string)?
+        } else if let Some(rest) = string
+            .strip_prefix('[')
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let inner = rest
+                .strip_suffix(']')
+                .ok_or(ElementSpecificationParsingError::UnclosedIsotope)?;
+            Self::split_prefix_isotope(inner)?
         } else {
-            Err(ElementSpecificationParsingError::UnknownElement)
+            match string.find('[') {
+                Some(iso_start) => {
+                    let iso_end = string[iso_start..]
+                        .find(']')
+                        .map(|i| i + iso_start)
+                        .ok_or(ElementSpecificationParsingError::UnclosedIsotope)?;
+                    if iso_end + 1 != string.len() {
+                        return Err(ElementSpecificationParsingError::MalformedIsotope);
+                    }
+                    let isotope = string[iso_start + 1..iso_end]
+                        .parse::<u16>()
+                        .map_err(|_| ElementSpecificationParsingError::MalformedIsotope)?;
+                    (&string[..iso_start], isotope)
+                }
+                None if string.contains(']') => {
+                    return Err(ElementSpecificationParsingError::MalformedIsotope)
+                }
+                None => (string, 0),
+            }
+        };
+        let (element, implied_isotope) = resolve_symbol(elt_sym, periodic_table)
+            .ok_or(ElementSpecificationParsingError::UnknownElement)?;
+        let spec = match (implied_isotope, isotope) {
+            (0, isotope) => ElementSpecification::new(element, isotope),
+            (implied, 0) => ElementSpecification::new(element, implied),
+            _ => return Err(ElementSpecificationParsingError::MalformedIsotope),
+        };
+        if !allow_unknown_isotopes && !spec.is_known_isotope() {
+            return Err(ElementSpecificationParsingError::UnknownIsotope(
+                spec.isotope,
+            ));
         }
+        Ok(spec)
     }
 }
 
@@ -198,4 +368,64 @@ mod test {
         assert_eq!(spec.isotope, 13);
         assert_eq!(spec.element.symbol, "C");
     }
+
+    #[test]
+    fn test_element_spec_parse_prefix() {
+        let c13 = ElementSpecification::parse("C[13]").unwrap();
+        for notation in ["[13C]", "^13C", "13C"] {
+            assert_eq!(ElementSpecification::parse(notation).unwrap(), c13);
+        }
+        let d = ElementSpecification::parse("D").unwrap();
+        assert_eq!(d, ElementSpecification::parse("H[2]").unwrap());
+        let t = ElementSpecification::parse("T").unwrap();
+        assert_eq!(t.isotope, 3);
+        assert!(t.is_known_isotope());
+        assert!((t.mass() - 3.016049).abs() < 1e-6);
+
+        use ElementSpecificationParsingError::*;
+        assert_eq!(ElementSpecification::parse("D[2]"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("^C"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("13"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("[13C"), Err(UnclosedIsotope));
+        assert_eq!(ElementSpecification::parse("^99C"), Err(UnknownIsotope(99)));
+    }
+
+    #[test]
+    fn test_element_spec_parse_errors() {
+        use ElementSpecificationParsingError::*;
+        assert_eq!(ElementSpecification::parse("C[x]"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("C[]"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("C[13]x"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("C13]"), Err(MalformedIsotope));
+        assert_eq!(ElementSpecification::parse("C[13"), Err(UnclosedIsotope));
+        assert_eq!(ElementSpecification::parse("Xy"), Err(UnknownElement));
+        assert_eq!(
+            ElementSpecification::parse("C[99]"),
+            Err(UnknownIsotope(99))
+        );
+
+        let spec = ElementSpecification::parse_lenient("C[99]").unwrap();
+        assert_eq!(spec.isotope, 99);
+        assert!(!spec.is_known_isotope());
+        let expected = 12.0 + 87.0 * NEUTRON_SHIFT;
+        assert!((spec.mass() - expected).abs() < 1e-6);
+        assert_eq!(spec.nominal_mass(), 99);
+    }
+
+    #[test]
+    fn test_element_spec_ord() {
+        let c = ElementSpecification::parse("C").unwrap();
+        let c13 = ElementSpecification::parse("C[13]").unwrap();
+        let n = ElementSpecification::parse("N").unwrap();
+        assert!(c < c13 && c13 < n);
+        assert_eq!(c.cmp(&c), cmp::Ordering::Equal);
+
+        // An element with the same symbol but another atomic number is neither
+        // equal nor ordered the same
+        let mut renumbered = PERIODIC_TABLE["C"].clone();
+        renumbered.element_number = 7;
+        let other = ElementSpecification::new(&renumbered, 0);
+        assert_ne!(c, other);
+        assert_ne!(c.cmp(&other), cmp::Ordering::Equal);
+    }
 }
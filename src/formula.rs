@@ -1,10 +1,12 @@
 use std::fmt::Display;
 use std::num::ParseIntError;
 
+use crate::abbreviation::AbbreviationRegistry;
 use crate::abstract_composition::{ChemicalComposition, ChemicalCompositionRef};
+use crate::element_specification::resolve_symbol;
 use crate::table::PERIODIC_TABLE;
 use crate::ElementSpecification;
-use crate::{Element, PeriodicTable};
+use crate::PeriodicTable;
 
 #[derive(Debug, Default)]
 pub enum FormulaParserState {
@@ -14,29 +16,240 @@ pub enum FormulaParserState {
     Isotope,
     IsotopeToCount,
     Count,
-    Group,
     GroupToGroupCount,
     GroupCount,
+    Charge,
+    Multiplier,
+    PrefixIsotope,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum FormulaParserError {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of problem a [`FormulaParserError`] describes
+pub enum FormulaParserErrorKind {
     InvalidStart,
     ElementCountMalformed,
     IsotopeCountMalformed,
     GroupCountMalformed,
     IncompleteFormula,
     InvalidElement,
+    UnknownElement,
+    UnknownIsotope,
+    ChargeMalformed,
+    MultiplierMalformed,
+    MismatchedBracket,
+    AmbiguousCharge,
+    AmbiguousMultiplier,
+}
+
+impl Display for FormulaParserErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::InvalidStart => "Invalid start of formula",
+            Self::ElementCountMalformed => "Malformed element count",
+            Self::IsotopeCountMalformed => "Malformed isotope",
+            Self::GroupCountMalformed => "Malformed group count",
+            Self::IncompleteFormula => "Incomplete formula",
+            Self::InvalidElement => "Unexpected character",
+            Self::UnknownElement => "Unknown element",
+            Self::UnknownIsotope => "Unknown isotope",
+            Self::ChargeMalformed => "Malformed charge",
+            Self::MultiplierMalformed => "Malformed component multiplier",
+            Self::MismatchedBracket => "Mismatched bracket",
+            Self::AmbiguousCharge => "Charge could be read as a negative count",
+            Self::AmbiguousMultiplier => "Multiplier could be read as an isotope",
+        };
+        f.write_str(description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+An error encountered while parsing a formula, pointing to where in the input
+the problem was found.
+
+```rust
+# use chemical_elements::{parse_formula, FormulaParserErrorKind};
+let err = parse_formula("CL2").unwrap_err();
+assert_eq!(err.kind, FormulaParserErrorKind::UnknownElement);
+assert_eq!(err.suggestion.as_deref(), Some("Cl"));
+assert_eq!(
+    err.to_string(),
+    "Unknown element \"CL\" at offset 0, did you mean \"Cl\"?\nCL2\n^^"
+);
+```
+*/
+pub struct FormulaParserError {
+    pub kind: FormulaParserErrorKind,
+    /// The byte offset of the offending token in `input`
+    pub offset: usize,
+    /// The offending token, which is empty if the formula ended unexpectedly
+    pub token: String,
+    /// Descriptions of what could have been accepted instead of `token`
+    pub expected: &'static [&'static str],
+    /// The nearest known element symbol, if `token` was an unknown element
+    pub suggestion: Option<String>,
+    /// The formula being parsed
+    pub input: String,
+    /// The offset of the unclosed bracket, if the error concerns a bracketed group
+    pub opened_at: Option<usize>,
+}
+
+impl FormulaParserError {
+    pub fn new(
+        kind: FormulaParserErrorKind,
+        input: &str,
+        offset: usize,
+        end: usize,
+        expected: &'static [&'static str],
+    ) -> Self {
+        Self {
+            kind,
+            offset,
+            token: input[offset..end].to_string(),
+            expected,
+            suggestion: None,
+            input: input.to_string(),
+            opened_at: None,
+        }
+    }
+
+    /// An error for an element symbol which is not in `periodic_table`, with a
+    /// suggestion of the nearest symbol that is.
+    ///
+    /// A symbol whose second letter was capitalized, like `CL`, is read as two
+    /// elements, so the preceding element symbol ending at `offset` is also
+    /// considered as part of the misspelling.
+    fn unknown_element(
+        input: &str,
+        offset: usize,
+        end: usize,
+        previous: Option<usize>,
+        periodic_table: &PeriodicTable,
+    ) -> Self {
+        let mut err = Self::new(
+            FormulaParserErrorKind::UnknownElement,
+            input,
+            offset,
+            end,
+            &["element symbol"],
+        );
+        if let Some(start) = previous {
+            if let Some(symbol) = periodic_table.nearest_symbol(&input[start..end]) {
+                if symbol.eq_ignore_ascii_case(&input[start..end]) {
+                    err.offset = start;
+                    err.token = input[start..end].to_string();
+                    err.suggestion = Some(symbol.to_string());
+                    return err;
+                }
+            }
+        }
+        err.suggestion = periodic_table
+            .nearest_symbol(&err.token)
+            .map(|s| s.to_string());
+        err
+    }
+
+    /// Render the input with a caret underlining the offending token, and
+    /// the bracket it concerns if there is one
+    pub fn caret_message(&self) -> String {
+        let padding = self.input[..self.offset].chars().count();
+        let width = self.token.chars().count().max(1);
+        let mut marks = " ".repeat(padding) + &"^".repeat(width);
+        if let Some(opened_at) = self.opened_at {
+            let column = self.input[..opened_at].chars().count();
+            let mut chars: Vec<char> = marks.chars().collect();
+            if chars.len() <= column {
+                chars.resize(column + 1, ' ');
+            }
+            chars[column] = '^';
+            marks = chars.into_iter().collect();
+        }
+        format!("{}\n{}", self.input, marks)
+    }
 }
 
 impl Display for FormulaParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        if self.token.is_empty() {
+            write!(f, "{} at offset {}", self.kind, self.offset)?;
+        } else {
+            write!(
+                f,
+                "{} {:?} at offset {}",
+                self.kind, self.token, self.offset
+            )?;
+        }
+        if let Some(opened_at) = self.opened_at {
+            write!(f, ", opened at offset {opened_at}")?;
+        }
+        if let Some(suggestion) = self.suggestion.as_ref() {
+            write!(f, ", did you mean {suggestion:?}?")?;
+        } else if !self.expected.is_empty() {
+            write!(f, ", expected {}", self.expected.join(" or "))?;
+        }
+        write!(f, "\n{}", self.caret_message())
     }
 }
 
 impl std::error::Error for FormulaParserError {}
 
+/// A group being read, which is added to its enclosing group once it is closed
+struct GroupFrame<'lifespan> {
+    /// The opening bracket and its offset, or `None` for the formula as a whole
+    opening: Option<(char, usize)>,
+    /// The elements and groups read so far in the current component
+    acc: ChemicalComposition<'lifespan>,
+    /// The sum of the completed components of an addition compound, and the
+    /// multiplier of the component being read
+    total: ChemicalComposition<'lifespan>,
+    multiplier: i32,
+    /// A group nested in this one which has been closed, awaiting its count
+    closed: Option<ChemicalComposition<'lifespan>>,
+}
+
+impl<'lifespan> GroupFrame<'lifespan> {
+    fn new(opening: Option<(char, usize)>) -> Self {
+        Self {
+            opening,
+            acc: ChemicalComposition::default(),
+            total: ChemicalComposition::default(),
+            multiplier: 1,
+            closed: None,
+        }
+    }
+
+    /// Add the current component to the total, and begin a new one
+    fn complete_component(&mut self) {
+        self.total += &(&self.acc * self.multiplier);
+        self.acc = ChemicalComposition::default();
+        self.multiplier = 1;
+    }
+
+    fn finish(mut self) -> ChemicalComposition<'lifespan> {
+        self.complete_component();
+        self.total
+    }
+}
+
+/// The bracket which closes the group opened by `c`
+fn closing_bracket(c: char) -> Option<char> {
+    match c {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+/// The [`FormulaParserError::expected`] bracket closing the group opened by `c`
+fn expected_closing(c: char) -> &'static [&'static str] {
+    match c {
+        '(' => &["')'"],
+        '[' => &["']'"],
+        _ => &["'}'"],
+    }
+}
+
 #[derive(Default)]
 pub struct FormulaParser {
     pub element_start: usize,
@@ -45,11 +258,30 @@ pub struct FormulaParser {
     pub isotope_end: usize,
     pub count_start: usize,
     pub count_end: usize,
-    pub paren_stack: i32,
-    pub group_start: usize,
-    pub group_end: usize,
     pub group_count_start: usize,
     pub group_count_end: usize,
+    pub charge_start: usize,
+    pub multiplier_start: usize,
+    pub prefix_isotope_start: usize,
+    pub prefix_isotope_end: usize,
+    /// Whether the current element had its isotope prefixed in brackets like `[13C]`,
+    /// and so must be closed by `]`
+    pub prefix_bracket: bool,
+    /// Whether a trailing charge like `+`, `-2` or `]2+` is permitted
+    pub allow_charge: bool,
+    /// The charge parsed from the end of the formula, if charges are permitted
+    pub charge: i32,
+    /// Whether isotopes which are not listed for their element, like synthetic
+    /// labels, are permitted
+    pub allow_unknown_isotopes: bool,
+    /// Whether digits at the start of a formula followed by an element with an
+    /// isotope of that mass number, like `13C6H12O6`, are read as its isotope
+    /// rather than as a multiplier. Such formulae, including `2H2O` and `12CH4`,
+    /// are rejected as ambiguous when this is not set, and `^13C` or `[13C]`
+    /// are preferred.
+    pub bare_prefix_isotopes: bool,
+    /// The start of the previous element symbol, if it ended where the current one begins
+    pub previous_element_start: Option<usize>,
     pub state: FormulaParserState,
 }
 
@@ -67,16 +299,40 @@ impl<'transient, 'lifespan: 'transient> FormulaParser {
         parser.parse_formula_with_table_generic(string, periodic_table)
     }
 
-    pub fn parse_element_from_string(
-        &mut self,
+    /// Parse a formula which may begin with a bare isotope like `13C6H12O6`,
+    /// reading its leading digits as a mass number rather than a multiplier
+    pub fn parse_with_bare_isotopes_with_table(
+        string: &'transient str,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> Result<ChemicalComposition<'lifespan>, FormulaParserError> {
+        let mut parser = Self {
+            bare_prefix_isotopes: true,
+            ..Self::default()
+        };
+        parser.parse_formula_with_table_generic(string, periodic_table)
+    }
+
+    /// Parse a formula with an optional trailing charge, like `C6H13O6+`, `SO4-2`
+    /// or `[C6H12O6]2+`, returning the composition and the charge.
+    ///
+    /// A formula with negative counts must enclose itself in brackets before its
+    /// charge, like `[H-2O-1]-`, as the charge of `H-2O-1` could be read as a count.
+    pub fn parse_charged(
         string: &str,
+    ) -> Result<(ChemicalComposition<'lifespan>, i32), FormulaParserError> {
+        Self::parse_charged_with_table(string, &PERIODIC_TABLE)
+    }
+
+    pub fn parse_charged_with_table(
+        string: &'transient str,
         periodic_table: &'lifespan PeriodicTable,
-    ) -> &'lifespan Element {
-        let elt_sym = &string[self.element_start..self.element_end];
-        let elt = &periodic_table[elt_sym];
-        self.element_start = 0;
-        self.element_end = 0;
-        elt
+    ) -> Result<(ChemicalComposition<'lifespan>, i32), FormulaParserError> {
+        let mut parser = Self {
+            allow_charge: true,
+            ..Self::default()
+        };
+        let composition = parser.parse_formula_with_table_generic(string, periodic_table)?;
+        Ok((composition, parser.charge))
     }
 
     pub fn parse_element_count(&mut self, string: &str) -> Result<i32, ParseIntError> {
@@ -93,76 +349,673 @@ impl<'transient, 'lifespan: 'transient> FormulaParser {
         count_parse
     }
 
-    pub fn handle_group_state(&mut self, c: char, i: usize) {
-        if c == ')' {
-            self.paren_stack -= 1;
-            if self.paren_stack == 0 {
-                self.group_end = i;
-                self.state = FormulaParserState::GroupToGroupCount;
+    /// Parse an isotope number spanning `start..end` of `string`
+    fn parse_isotope_number(
+        string: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<u16, FormulaParserError> {
+        string[start..end].parse::<u16>().map_err(|_| {
+            FormulaParserError::new(
+                FormulaParserErrorKind::IsotopeCountMalformed,
+                string,
+                start,
+                end,
+                &["isotope number"],
+            )
+        })
+    }
+
+    /**
+    Parse the element symbol that was just read, along with its isotope, which
+    may have been written before the symbol like `^13C`, after it like `C[13]`,
+    or implied by the symbol like `D`. The isotope must be known for the element
+    unless unknown isotopes are permitted.
+    */
+    fn parse_element_spec(
+        &mut self,
+        string: &str,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> Result<ElementSpecification<'lifespan>, FormulaParserError> {
+        let elt_sym = &string[self.element_start..self.element_end];
+        let (element, implied_isotope) =
+            resolve_symbol(elt_sym, periodic_table).ok_or_else(|| {
+                FormulaParserError::unknown_element(
+                    string,
+                    self.element_start,
+                    self.element_end,
+                    self.previous_element_start,
+                    periodic_table,
+                )
+            })?;
+
+        let explicit = if self.isotope_end != self.isotope_start {
+            Some((self.isotope_start, self.isotope_end))
+        } else if self.prefix_isotope_end != self.prefix_isotope_start {
+            Some((self.prefix_isotope_start, self.prefix_isotope_end))
+        } else {
+            None
+        };
+        let isotope = match (explicit, implied_isotope) {
+            (None, implied) => implied,
+            (Some((start, end)), 0) => {
+                let isotope = Self::parse_isotope_number(string, start, end)?;
+                if !(self.allow_unknown_isotopes
+                    || ElementSpecification::new(element, isotope).is_known_isotope())
+                {
+                    return Err(FormulaParserError::new(
+                        FormulaParserErrorKind::UnknownIsotope,
+                        string,
+                        start,
+                        end,
+                        &["isotope of the element"],
+                    ));
+                }
+                isotope
+            }
+            (Some((start, end)), _) => {
+                return Err(FormulaParserError::new(
+                    FormulaParserErrorKind::IsotopeCountMalformed,
+                    string,
+                    start,
+                    end,
+                    &["no isotope for an isotope symbol"],
+                ));
+            }
+        };
+
+        self.previous_element_start = None;
+        self.element_start = 0;
+        self.element_end = 0;
+        self.isotope_start = 0;
+        self.isotope_end = 0;
+        self.prefix_isotope_start = 0;
+        self.prefix_isotope_end = 0;
+        Ok(ElementSpecification::new(element, isotope))
+    }
+
+    /// Begin reading an isotope prefixed to an element like `^13C` or `[13C]`,
+    /// if `c` starts one
+    fn start_prefix_isotope(&mut self, string: &str, c: char, i: usize) -> bool {
+        let bracketed = c == '[' && string[i + 1..].starts_with(|c: char| c.is_ascii_digit());
+        if c == '^' || bracketed {
+            self.prefix_bracket = bracketed;
+            self.prefix_isotope_start = i + 1;
+            self.prefix_isotope_end = i + 1;
+            self.state = FormulaParserState::PrefixIsotope;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the `[` at `i` encloses the isotope of the preceding element like
+    /// `C[13]`, rather than opening a group
+    fn is_suffix_isotope(string: &str, i: usize) -> bool {
+        let rest = &string[i + 1..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        digits > 0 && rest[digits..].starts_with(']')
+    }
+
+    /**
+    Whether the digits at the start of the formula ending at `i` are the mass
+    number of the element starting at `i`, like `13C6H12O6`, rather than a
+    multiplier like `2CaSO4·H2O`, because the element has an isotope with that
+    mass number. Whether they are then read as an isotope depends upon
+    [`FormulaParser::bare_prefix_isotopes`].
+    */
+    fn is_bare_prefix_isotope(
+        &self,
+        string: &str,
+        i: usize,
+        periodic_table: &'lifespan PeriodicTable,
+    ) -> bool {
+        if self.multiplier_start != 0 {
+            return false;
+        }
+        let symbol_len = string[i + 1..]
+            .find(|c: char| !c.is_ascii_lowercase())
+            .unwrap_or(string.len() - i - 1)
+            + 1;
+        match (
+            periodic_table.get(&string[i..i + symbol_len]),
+            string[..i].parse::<u16>(),
+        ) {
+            // Only naturally occurring isotopes, so `3H2O` stays three waters
+            (Some(element), Ok(isotope)) => element
+                .isotopes
+                .get(&isotope)
+                .is_some_and(|iso| iso.abundance > 0.0),
+            _ => false,
+        }
+    }
+
+    /// Open a group with the bracket `c` at `i`
+    fn open_group(&mut self, c: char, i: usize, frames: &mut Vec<GroupFrame<'lifespan>>) {
+        frames.push(GroupFrame::new(Some((c, i))));
+        self.state = FormulaParserState::New;
+    }
+
+    /**
+    Close the innermost group with the bracket `c` at `i`, checking that it
+    matches the bracket the group was opened with.
+
+    When charges are permitted, a formula enclosed in square brackets followed
+    only by a charge, like `[Fe(CN)6]4-`, is read as an ion rather than as a
+    group with a count.
+    */
+    fn close_group(
+        &mut self,
+        string: &str,
+        c: char,
+        i: usize,
+        frames: &mut Vec<GroupFrame<'lifespan>>,
+    ) -> Result<(), FormulaParserError> {
+        let opening = frames.last().and_then(|frame| frame.opening);
+        let (open, open_at) = match opening {
+            Some(opening) => opening,
+            None => {
+                return Err(FormulaParserError::new(
+                    FormulaParserErrorKind::MismatchedBracket,
+                    string,
+                    i,
+                    i + c.len_utf8(),
+                    &[],
+                ));
+            }
+        };
+        if closing_bracket(open) != Some(c) {
+            let mut err = FormulaParserError::new(
+                FormulaParserErrorKind::MismatchedBracket,
+                string,
+                i,
+                i + c.len_utf8(),
+                expected_closing(open),
+            );
+            err.opened_at = Some(open_at);
+            return Err(err);
+        }
+
+        let group = frames.pop().unwrap().finish();
+        let rest = &string[i + 1..];
+        let is_ion = self.allow_charge
+            && open == '['
+            && open_at == 0
+            && frames.len() == 1
+            && rest.contains(['+', '-'])
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '+' || c == '-');
+        let parent = frames.last_mut().unwrap();
+        if is_ion {
+            parent.acc += &group;
+            self.charge_start = i + 1;
+            self.state = FormulaParserState::Charge;
+        } else {
+            parent.closed = Some(group);
+            self.state = FormulaParserState::GroupToGroupCount;
+        }
+        Ok(())
+    }
+
+    /// What could follow a complete element or group at this point in the formula
+    fn expected_next(&self) -> &'static [&'static str] {
+        if self.allow_charge {
+            &["element symbol", "'('", "'['", "'{'", "charge"]
+        } else {
+            &["element symbol", "'('", "'['", "'{'"]
+        }
+    }
+
+    /// Begin reading an element or group at `c`, after a complete element or group
+    fn start_next_token(
+        &mut self,
+        string: &str,
+        c: char,
+        i: usize,
+        frames: &mut Vec<GroupFrame<'lifespan>>,
+    ) -> Result<(), FormulaParserError> {
+        if self.start_prefix_isotope(string, c, i) {
+            return Ok(());
+        }
+        if closing_bracket(c).is_some() {
+            self.open_group(c, i, frames);
+        } else if c.is_ascii_uppercase() || Self::starts_electron(string, i) {
+            self.element_start = i;
+            self.state = FormulaParserState::Element;
+        } else {
+            return Err(FormulaParserError::new(
+                FormulaParserErrorKind::InvalidElement,
+                string,
+                i,
+                i + c.len_utf8(),
+                self.expected_next(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse a charge suffix, either a run of signs like `++`, or a sign
+    /// and a magnitude in either order like `+2` or `2+`
+    fn parse_charge(string: &str, start: usize) -> Result<i32, FormulaParserError> {
+        let charge = &string[start..];
+        if charge.is_empty() {
+            return Ok(0);
+        }
+        let malformed = || {
+            FormulaParserError::new(
+                FormulaParserErrorKind::ChargeMalformed,
+                string,
+                start,
+                string.len(),
+                &["'+' or '-' with an optional non-zero magnitude"],
+            )
+        };
+        let digits = charge.trim_matches(|c| c == '+' || c == '-');
+        let sign_char = charge
+            .chars()
+            .find(|c| *c == '+' || *c == '-')
+            .ok_or_else(malformed)?;
+        let signs = charge.len() - digits.len();
+        if charge.matches(sign_char).count() != signs || !digits.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(malformed());
+        }
+        let sign = if sign_char == '+' { 1 } else { -1 };
+        if digits.is_empty() {
+            Ok(sign * charge.len() as i32)
+        } else if signs == 1 && (charge.starts_with(digits) || charge.ends_with(digits)) {
+            match digits.parse::<i32>() {
+                Ok(0) | Err(_) => Err(malformed()),
+                Ok(val) => Ok(sign * val),
             }
-        } else if c == '(' {
-            self.paren_stack += 1;
+        } else {
+            Err(malformed())
         }
     }
 
+    /// Complete whichever element or group is being read when it ends at `i`,
+    /// adding it to `frame`
+    fn complete_token(
+        &mut self,
+        string: &str,
+        i: usize,
+        periodic_table: &'lifespan PeriodicTable,
+        frame: &mut GroupFrame<'lifespan>,
+    ) -> Result<(), FormulaParserError> {
+        match self.state {
+            FormulaParserState::Element if self.prefix_bracket => {
+                return Err(FormulaParserError::new(
+                    FormulaParserErrorKind::IncompleteFormula,
+                    string,
+                    i,
+                    i,
+                    &["']'"],
+                ));
+            }
+            FormulaParserState::Element => {
+                self.element_end = i;
+                let elt_spec = self.parse_element_spec(string, periodic_table)?;
+                frame.acc.inc(elt_spec, 1);
+            }
+            FormulaParserState::IsotopeToCount => {
+                let elt_spec = self.parse_element_spec(string, periodic_table)?;
+                frame.acc.inc(elt_spec, 1);
+            }
+            FormulaParserState::Count => {
+                self.count_end = i;
+                let count_start = self.count_start;
+                let count: i32 = match self.parse_element_count(string) {
+                    Ok(val) => val,
+                    Err(_msg) => {
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::ElementCountMalformed,
+                            string,
+                            count_start,
+                            i,
+                            &["element count"],
+                        ));
+                    }
+                };
+                let elt_spec = self.parse_element_spec(string, periodic_table)?;
+                frame.acc.inc(elt_spec, count);
+            }
+            FormulaParserState::GroupToGroupCount => {
+                if let Some(group) = frame.closed.take() {
+                    frame.acc += &group;
+                }
+            }
+            FormulaParserState::GroupCount => {
+                self.group_count_end = i;
+                let group_count_start = self.group_count_start;
+                let group_count: i32 = match self.parse_group_count(string) {
+                    Ok(val) => val,
+                    Err(_msg) => {
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::GroupCountMalformed,
+                            string,
+                            group_count_start,
+                            i,
+                            &["group count"],
+                        ));
+                    }
+                };
+                if let Some(group) = frame.closed.take() {
+                    frame.acc += &(&group * group_count);
+                }
+            }
+            _ => {
+                let expected: &'static [&'static str] = match self.state {
+                    FormulaParserState::New | FormulaParserState::Multiplier => {
+                        &["element symbol", "'('", "'['", "'{'"]
+                    }
+                    FormulaParserState::PrefixIsotope => &["digit", "element symbol"],
+                    FormulaParserState::Isotope => &["']'"],
+                    _ => &[],
+                };
+                return Err(FormulaParserError::new(
+                    FormulaParserErrorKind::IncompleteFormula,
+                    string,
+                    i,
+                    i,
+                    expected,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether an element or group is complete, or could be completed, at this point
+    fn can_complete_token(&self) -> bool {
+        match self.state {
+            FormulaParserState::Element => !self.prefix_bracket,
+            FormulaParserState::Count
+            | FormulaParserState::IsotopeToCount
+            | FormulaParserState::GroupToGroupCount
+            | FormulaParserState::GroupCount => true,
+            _ => false,
+        }
+    }
+
+    /**
+    Whether the `-` at `i` begins a negative count, like the `-2` of `H-2O`,
+    rather than a charge. A negative count must be followed by more of the
+    formula, so a trailing `-2` is read as a charge when charges are permitted.
+    */
+    fn is_signed_count(string: &str, c: char, i: usize) -> bool {
+        if c != '-' {
+            return false;
+        }
+        let rest = &string[i + 1..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        digits > 0 && rest[digits..].starts_with(|c: char| c != '+' && c != '-')
+    }
+
+    /// Whether `c` separates the components of an addition compound, like
+    /// the water of a hydrate in `CuSO4·5H2O`
+    fn is_component_separator(c: char) -> bool {
+        matches!(c, '·' | '.' | '*')
+    }
+
+    /// Whether the electron pseudo-element `e*` starts at `i`. It may only begin
+    /// a component or follow a count, bracket or group, as in `Na1e*-1`, since
+    /// after a bare symbol the `e` would extend it.
+    fn starts_electron(string: &str, i: usize) -> bool {
+        string[i..].starts_with("e*")
+    }
+
+    /// Whether `i` is the `*` of an `e*` symbol being read, rather than a
+    /// component separator
+    fn reads_electron(&self, string: &str, i: usize) -> bool {
+        matches!(self.state, FormulaParserState::Element)
+            && self.element_start + 1 == i
+            && Self::starts_electron(string, self.element_start)
+    }
+
+    /// Begin reading the first element or group of a component at `c`
+    fn start_component(
+        &mut self,
+        string: &str,
+        c: char,
+        i: usize,
+        frames: &mut Vec<GroupFrame<'lifespan>>,
+    ) -> Result<(), FormulaParserError> {
+        if self.start_prefix_isotope(string, c, i) {
+            return Ok(());
+        }
+        if closing_bracket(c).is_some() {
+            self.open_group(c, i, frames);
+        } else if c.is_ascii_uppercase() || Self::starts_electron(string, i) {
+            self.element_start = i;
+            self.state = FormulaParserState::Element;
+        } else {
+            return Err(FormulaParserError::new(
+                FormulaParserErrorKind::InvalidStart,
+                string,
+                i,
+                i + c.len_utf8(),
+                &["element symbol", "'('", "'['", "'{'", "multiplier"],
+            ));
+        }
+        Ok(())
+    }
+
+    /**
+    Read the abbreviation starting at `i` if an element symbol was just begun
+    there, treating it like a closed group so that it may be followed by a count.
+    Returns the end of the abbreviation.
+    */
+    fn start_abbreviation(
+        &mut self,
+        string: &str,
+        i: usize,
+        abbreviations: &AbbreviationRegistry<'lifespan>,
+        frame: &mut GroupFrame<'lifespan>,
+    ) -> Option<usize> {
+        let starts_element = matches!(self.state, FormulaParserState::Element)
+            && self.element_start == i
+            && !self.prefix_bracket
+            && self.prefix_isotope_start == self.prefix_isotope_end;
+        if !starts_element {
+            return None;
+        }
+        let (name, composition) = abbreviations.find_prefix(&string[i..])?;
+        frame.closed = Some(composition.clone());
+        self.previous_element_start = None;
+        self.element_start = 0;
+        self.state = FormulaParserState::GroupToGroupCount;
+        Some(i + name.len())
+    }
+
     pub fn parse_formula_with_table_generic<C: From<ChemicalComposition<'lifespan>>>(
         &mut self,
         string: &str,
         periodic_table: &'lifespan PeriodicTable,
     ) -> Result<C, FormulaParserError> {
-        let mut acc = ChemicalComposition::default();
+        self.parse_formula_with_abbreviations_generic(string, periodic_table, None)
+    }
+
+    /// Parse a formula which may use the abbreviations in `abbreviations`, like
+    /// the `Ph` and `Ac` of `PhCH2OAc`
+    pub fn parse_formula_with_abbreviations_generic<C: From<ChemicalComposition<'lifespan>>>(
+        &mut self,
+        string: &str,
+        periodic_table: &'lifespan PeriodicTable,
+        abbreviations: Option<&AbbreviationRegistry<'lifespan>>,
+    ) -> Result<C, FormulaParserError> {
+        // The groups being read, with the innermost last. Groups are completed
+        // when they are closed, so nothing is read twice.
+        let mut frames = vec![GroupFrame::new(None)];
         let n = string.len();
+        // The end of the abbreviation being read, whose characters are skipped
+        let mut abbreviation_end = 0;
 
         for (i, c) in string.char_indices() {
+            if i < abbreviation_end {
+                continue;
+            }
+            if Self::is_component_separator(c)
+                && self.can_complete_token()
+                && !self.reads_electron(string, i)
+            {
+                let frame = frames.last_mut().unwrap();
+                self.complete_token(string, i, periodic_table, frame)?;
+                frame.complete_component();
+                self.state = FormulaParserState::New;
+                continue;
+            }
+            if matches!(c, ')' | ']' | '}') && self.can_complete_token() {
+                self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                self.close_group(string, c, i, &mut frames)?;
+                continue;
+            }
+            if self.allow_charge
+                && (c == '+' || c == '-')
+                && !Self::is_signed_count(string, c, i)
+                && self.can_complete_token()
+            {
+                // Any earlier `-` was a negative count, so this charge might be one too
+                if string[..i].contains('-') {
+                    return Err(FormulaParserError::new(
+                        FormulaParserErrorKind::AmbiguousCharge,
+                        string,
+                        i,
+                        n,
+                        &["formula enclosed in '[]' before its charge"],
+                    ));
+                }
+                self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                self.charge_start = i;
+                self.state = FormulaParserState::Charge;
+                continue;
+            }
             match self.state {
                 FormulaParserState::New => {
-                    if c.is_ascii_alphabetic() && c.is_ascii_uppercase() {
+                    if c.is_ascii_digit() {
+                        self.multiplier_start = i;
+                        self.state = FormulaParserState::Multiplier;
+                    } else {
+                        self.start_component(string, c, i, &mut frames)?;
+                    }
+                }
+                FormulaParserState::Multiplier => {
+                    if c.is_ascii_uppercase()
+                        && self.is_bare_prefix_isotope(string, i, periodic_table)
+                    {
+                        if !self.bare_prefix_isotopes {
+                            return Err(FormulaParserError::new(
+                                FormulaParserErrorKind::AmbiguousMultiplier,
+                                string,
+                                self.multiplier_start,
+                                i,
+                                &[
+                                    "'^' or '[]' around an isotope",
+                                    "'()' around a multiplied formula",
+                                ],
+                            ));
+                        }
+                        self.prefix_isotope_start = self.multiplier_start;
+                        self.prefix_isotope_end = i;
+                        self.element_start = i;
+                        self.state = FormulaParserState::Element;
+                    } else if !c.is_ascii_digit() {
+                        let multiplier = match string[self.multiplier_start..i].parse::<i32>() {
+                            Ok(val) if val > 0 => val,
+                            _ => {
+                                return Err(FormulaParserError::new(
+                                    FormulaParserErrorKind::MultiplierMalformed,
+                                    string,
+                                    self.multiplier_start,
+                                    i,
+                                    &["positive component multiplier"],
+                                ));
+                            }
+                        };
+                        frames.last_mut().unwrap().multiplier = multiplier;
+                        self.start_component(string, c, i, &mut frames)?;
+                    }
+                }
+                FormulaParserState::Charge => {
+                    if !(c.is_ascii_digit() || c == '+' || c == '-') {
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::ChargeMalformed,
+                            string,
+                            i,
+                            i + c.len_utf8(),
+                            &["'+'", "'-'", "digit"],
+                        ));
+                    }
+                }
+                FormulaParserState::PrefixIsotope => {
+                    if c.is_ascii_uppercase() && self.prefix_isotope_end > self.prefix_isotope_start
+                    {
                         self.element_start = i;
                         self.state = FormulaParserState::Element;
-                    } else if c == '(' {
-                        self.paren_stack += 1;
-                        self.group_start = i + 1;
-                        self.state = FormulaParserState::Group;
+                    } else if c.is_ascii_digit() {
+                        self.prefix_isotope_end = i + 1;
                     } else {
-                        return Err(FormulaParserError::InvalidStart);
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::IsotopeCountMalformed,
+                            string,
+                            i,
+                            i + c.len_utf8(),
+                            &["digit", "element symbol"],
+                        ));
                     }
                 }
-                FormulaParserState::Group => {
-                    self.handle_group_state(c, i);
+                FormulaParserState::Element if self.prefix_bracket => {
+                    if c == ']' {
+                        self.element_end = i;
+                        self.prefix_bracket = false;
+                        self.state = FormulaParserState::IsotopeToCount;
+                    } else if !c.is_ascii_lowercase() {
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::IsotopeCountMalformed,
+                            string,
+                            i,
+                            i + c.len_utf8(),
+                            &["']'"],
+                        ));
+                    }
                 }
                 FormulaParserState::Element => {
-                    if c.is_ascii_alphabetic() {
+                    if self.reads_electron(string, i) {
+                        // The `*` of `e*` is part of the symbol
+                    } else if c.is_ascii_alphabetic() {
                         if c.is_uppercase() {
                             self.element_end = i;
-                            let elt = self.parse_element_from_string(string, periodic_table);
-                            let elt_spec = ElementSpecification {
-                                element: elt,
-                                isotope: 0,
-                            };
-                            acc.inc(elt_spec, 1);
+                            let previous = self.element_start;
+                            let elt_spec = self.parse_element_spec(string, periodic_table)?;
+                            frames.last_mut().unwrap().acc.inc(elt_spec, 1);
                             self.state = FormulaParserState::Element;
+                            self.previous_element_start = Some(previous);
                             self.element_start = i;
                             self.element_end = 0;
                         }
-                    } else if c.is_numeric() {
+                    } else if c.is_numeric() || c == '-' {
                         self.element_end = i;
                         self.count_start = i;
                         self.state = FormulaParserState::Count;
-                    } else if c == '[' {
+                    } else if c == '[' && Self::is_suffix_isotope(string, i) {
+                        self.element_end = i;
                         self.isotope_start = i + 1;
                         self.state = FormulaParserState::Isotope;
-                    } else if c == '(' {
-                        self.element_end = i;
-                        let elt = self.parse_element_from_string(string, periodic_table);
-                        let elt_spec = ElementSpecification {
-                            element: elt,
-                            isotope: 0,
+                    } else if c == '^' || closing_bracket(c).is_some() {
+                        self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                        self.start_next_token(string, c, i, &mut frames)?;
+                    } else {
+                        let expected: &'static [&'static str] = if self.allow_charge {
+                            &["element symbol", "count", "'['", "'('", "'{'", "charge"]
+                        } else {
+                            &["element symbol", "count", "'['", "'('", "'{'"]
                         };
-                        acc.inc(elt_spec, 1);
-
-                        self.paren_stack += 1;
-                        self.group_start = i + 1;
-                        self.state = FormulaParserState::Group;
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::InvalidElement,
+                            string,
+                            i,
+                            i + c.len_utf8(),
+                            expected,
+                        ));
                     }
                 }
                 FormulaParserState::Isotope => {
@@ -170,103 +1023,34 @@ impl<'transient, 'lifespan: 'transient> FormulaParser {
                         self.isotope_end = i;
                         self.state = FormulaParserState::IsotopeToCount;
                     } else if !c.is_numeric() {
-                        return Err(FormulaParserError::IsotopeCountMalformed);
+                        return Err(FormulaParserError::new(
+                            FormulaParserErrorKind::IsotopeCountMalformed,
+                            string,
+                            i,
+                            i + c.len_utf8(),
+                            &["digit", "']'"],
+                        ));
                     }
                 }
                 FormulaParserState::Count => {
                     if !c.is_numeric() {
-                        self.count_end = i;
-                        let count_parse = self.parse_element_count(string);
-                        let count: i32 = match count_parse {
-                            Ok(val) => val,
-                            Err(_msg) => {
-                                return Err(FormulaParserError::ElementCountMalformed);
-                            }
-                        };
-                        let isotope: u16 = if self.isotope_end != self.isotope_start {
-                            match string[self.isotope_start..self.isotope_end].parse::<u16>() {
-                                Ok(val) => val,
-                                Err(_msg) => {
-                                    return Err(FormulaParserError::IsotopeCountMalformed);
-                                }
-                            }
-                        } else {
-                            0
-                        };
-
-                        let elt = self.parse_element_from_string(string, periodic_table);
-                        let elt_spec = ElementSpecification {
-                            element: elt,
-                            isotope,
-                        };
-                        acc.inc(elt_spec, count);
-                        self.isotope_start = 0;
-                        self.isotope_end = 0;
-
-                        if c == '(' {
-                            self.paren_stack = 1;
-                            self.group_start = i + 1;
-                            self.state = FormulaParserState::Group;
-                        } else if c.is_ascii_alphabetic() && c.is_ascii_uppercase() {
-                            self.element_start = i;
-                            self.state = FormulaParserState::Element;
-                        } else {
-                            return Err(FormulaParserError::InvalidElement);
-                        }
+                        self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                        self.start_next_token(string, c, i, &mut frames)?;
                     }
                 }
                 FormulaParserState::IsotopeToCount => {
-                    if c.is_numeric() {
+                    if c.is_numeric() || c == '-' {
                         self.count_start = i;
                         self.state = FormulaParserState::Count;
                     } else {
-                        let elt = self.parse_element_from_string(string, periodic_table);
-                        let isotope: u16 =
-                            match string[self.isotope_start..self.isotope_end].parse::<u16>() {
-                                Ok(val) => val,
-                                Err(_msg) => {
-                                    return Err(FormulaParserError::IsotopeCountMalformed);
-                                }
-                            };
-                        let elt_spec = ElementSpecification {
-                            element: elt,
-                            isotope,
-                        };
-                        acc.inc(elt_spec, 1);
-                        self.isotope_start = 0;
-                        self.isotope_end = 0;
-
-                        if c == '(' {
-                            self.paren_stack += 1;
-                            self.group_start = i + 1;
-                            self.state = FormulaParserState::Group;
-                        } else if c.is_ascii_uppercase() {
-                            self.element_start = i;
-                            self.state = FormulaParserState::Element;
-                        } else {
-                            return Err(FormulaParserError::IsotopeCountMalformed);
-                        }
+                        self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                        self.start_next_token(string, c, i, &mut frames)?;
                     }
                 }
                 FormulaParserState::GroupToGroupCount => {
-                    if !c.is_numeric() {
-                        let group = Self::parse_with_table(
-                            &string[self.group_start..self.group_end],
-                            periodic_table,
-                        )?;
-                        self.group_start = 0;
-                        self.group_end = 0;
-                        acc += &group;
-                        if c == '(' {
-                            self.paren_stack = 1;
-                            self.group_start = i + 1;
-                            self.state = FormulaParserState::Group;
-                        } else if c.is_ascii_alphabetic() && c.is_ascii_uppercase() {
-                            self.element_start = i;
-                            self.state = FormulaParserState::Element;
-                        } else {
-                            return Err(FormulaParserError::InvalidElement);
-                        }
+                    if !(c.is_numeric() || c == '-') {
+                        self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                        self.start_next_token(string, c, i, &mut frames)?;
                     } else {
                         self.group_count_start = i;
                         self.state = FormulaParserState::GroupCount;
@@ -274,100 +1058,37 @@ impl<'transient, 'lifespan: 'transient> FormulaParser {
                 }
                 FormulaParserState::GroupCount => {
                     if !c.is_numeric() {
-                        self.group_count_end = i;
-                        let group = Self::parse_with_table(
-                            &string[self.group_start..self.group_end],
-                            periodic_table,
-                        )?;
-                        self.group_start = 0;
-                        self.group_end = 0;
-
-                        let group_count: i32 = match self.parse_group_count(string) {
-                            Ok(val) => val,
-                            Err(_msg) => {
-                                return Err(FormulaParserError::ElementCountMalformed);
-                            }
-                        };
-                        acc += &(&group * group_count);
-
-                        if c == '(' {
-                            self.paren_stack = 1;
-                            self.group_start = i + 1;
-                            self.state = FormulaParserState::Group;
-                        } else if c.is_ascii_alphabetic() && c.is_ascii_uppercase() {
-                            self.element_start = i;
-                            self.state = FormulaParserState::Element;
-                        } else {
-                            return Err(FormulaParserError::InvalidElement);
-                        }
+                        self.complete_token(string, i, periodic_table, frames.last_mut().unwrap())?;
+                        self.start_next_token(string, c, i, &mut frames)?;
                     }
                 }
             }
+            if let Some(abbreviations) = abbreviations {
+                let frame = frames.last_mut().unwrap();
+                if let Some(end) = self.start_abbreviation(string, i, abbreviations, frame) {
+                    abbreviation_end = end;
+                }
+            }
         }
 
-        let i = n;
+        if let Some((open, open_at)) = frames.last().and_then(|frame| frame.opening) {
+            let mut err = FormulaParserError::new(
+                FormulaParserErrorKind::IncompleteFormula,
+                string,
+                n,
+                n,
+                expected_closing(open),
+            );
+            err.opened_at = Some(open_at);
+            return Err(err);
+        }
         match self.state {
-            FormulaParserState::Element => {
-                self.element_end = i;
-                let elt = self.parse_element_from_string(string, periodic_table);
-                let elt_spec = ElementSpecification {
-                    element: elt,
-                    isotope: 0,
-                };
-                acc.inc(elt_spec, 1);
-            }
-            FormulaParserState::Count => {
-                self.count_end = i;
-                let count: i32 = match self.parse_element_count(string) {
-                    Ok(val) => val,
-                    Err(_msg) => {
-                        return Err(FormulaParserError::ElementCountMalformed);
-                    }
-                };
-                let isotope: u16 = if self.isotope_end != self.isotope_start {
-                    match string[self.isotope_start..self.isotope_end].parse::<u16>() {
-                        Ok(val) => val,
-                        Err(_msg) => {
-                            return Err(FormulaParserError::IsotopeCountMalformed);
-                        }
-                    }
-                } else {
-                    0
-                };
-                let elt = self.parse_element_from_string(string, periodic_table);
-                let elt_spec = ElementSpecification {
-                    element: elt,
-                    isotope,
-                };
-                acc.inc(elt_spec, count);
-            }
-            FormulaParserState::GroupToGroupCount => {
-                let group = Self::parse_with_table(
-                    &string[self.group_start..self.group_end],
-                    periodic_table,
-                )?;
-                acc += &group;
-            }
-            FormulaParserState::GroupCount => {
-                self.group_count_end = i;
-                let group = Self::parse_with_table(
-                    &string[self.group_start..self.group_end],
-                    periodic_table,
-                )?;
-                self.group_start = 0;
-                self.group_end = 0;
-
-                let group_count: i32 = match self.parse_group_count(string) {
-                    Ok(val) => val,
-                    Err(_msg) => {
-                        return Err(FormulaParserError::GroupCountMalformed);
-                    }
-                };
-                acc += &(&group * group_count);
+            FormulaParserState::Charge => {
+                self.charge = Self::parse_charge(string, self.charge_start)?;
             }
-            _ => return Err(FormulaParserError::IncompleteFormula),
+            _ => self.complete_token(string, n, periodic_table, frames.last_mut().unwrap())?,
         }
-        Ok(acc.into())
+        Ok(frames.pop().unwrap().finish().into())
     }
 }
 
@@ -384,35 +1105,343 @@ pub fn parse_formula_with_table<'lifespan>(
     FormulaParser::parse_with_table(string, periodic_table)
 }
 
-pub fn to_formula<'inner, 'lifespan: 'inner, C>(composition: &'inner C) -> String
+/// Parse a formula which may begin with a bare isotope like `13C6H12O6`, as written
+/// by [`IsotopeNotation::Prefix`]. Leading digits are read as a mass number when the
+/// element that follows has an isotope with that mass number, so `2H2O` is `D2O`.
+pub fn parse_formula_with_bare_isotopes<'lifespan>(
+    string: &str,
+) -> Result<ChemicalComposition<'lifespan>, FormulaParserError> {
+    FormulaParser::parse_with_bare_isotopes_with_table(string, &PERIODIC_TABLE)
+}
+
+pub fn parse_formula_with_bare_isotopes_with_table<'lifespan>(
+    string: &str,
+    periodic_table: &'lifespan PeriodicTable,
+) -> Result<ChemicalComposition<'lifespan>, FormulaParserError> {
+    FormulaParser::parse_with_bare_isotopes_with_table(string, periodic_table)
+}
+
+/// Parse a formula with an optional trailing charge like `C6H13O6+`, `SO4-2`,
+/// `Fe+3` or `[C6H12O6]2+`, returning the composition and its charge
+pub fn parse_charged_formula<'lifespan>(
+    string: &str,
+) -> Result<(ChemicalComposition<'lifespan>, i32), FormulaParserError> {
+    FormulaParser::parse_charged(string)
+}
+
+pub fn parse_charged_formula_with_table<'lifespan>(
+    string: &str,
+    periodic_table: &'lifespan PeriodicTable,
+) -> Result<(ChemicalComposition<'lifespan>, i32), FormulaParserError> {
+    FormulaParser::parse_charged_with_table(string, periodic_table)
+}
+
+/// Write `composition` as a formula followed by `charge`, which can be read back
+/// with [`parse_charged_formula`]
+pub fn to_charged_formula<'inner, 'lifespan: 'inner, C>(
+    composition: &'inner C,
+    charge: i32,
+) -> String
 where
     &'inner C: Into<ChemicalCompositionRef<'inner, 'lifespan>> + 'inner,
 {
-    let composition: ChemicalCompositionRef<'inner, 'lifespan> = composition.into();
-    let mut result = String::with_capacity(composition.len() * 2);
-    let carbon_count = composition["C"];
-    if carbon_count != 0 {
-        result.push('C');
-        result.push_str(&carbon_count.to_string());
-    }
-    let carbon_count = composition["H"];
-    if carbon_count != 0 {
-        result.push('H');
-        result.push_str(&carbon_count.to_string());
-    }
-    let mut items: Vec<(&ElementSpecification, &i32)> = composition.iter().collect();
-    items.sort_by(|a, b| a.0.element.symbol.cmp(&b.0.element.symbol));
-    for (key, count) in items {
-        // Skip the C and N
-        if ((key.element.symbol == "C") || (key.element.symbol == "H")) && key.isotope == 0 {
-            continue;
-        } else if key.isotope != 0 {
-            result.push_str(&format!("{}[{}]{}", key.element.symbol, key.isotope, count));
-        } else {
-            result.push_str(&format!("{}{}", key.element.symbol, count));
+    FormulaFormatter::new().charge(charge).format(composition)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How [`to_formula_with_notation`] writes elements with a fixed isotope
+pub enum IsotopeNotation {
+    /// After the symbol, like `C[13]6`
+    #[default]
+    Suffix,
+    /// Before the symbol, in brackets, like `[13C]6`
+    BracketPrefix,
+    /// Before the symbol, after a caret, like `^13C6`
+    Caret,
+    /// Before the symbol, like `13C6`. Labeled elements are written first, but
+    /// a formula with more than one can't be read back unambiguously, and one
+    /// can only be read back with [`parse_formula_with_bare_isotopes`].
+    Prefix,
+    /// Like [`IsotopeNotation::Suffix`], except that deuterium and tritium are
+    /// written as `D` and `T`
+    HydrogenSymbols,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The order in which a [`FormulaFormatter`] writes elements
+pub enum ElementOrder {
+    /// Unlabeled carbon and hydrogen first, then everything else alphabetically,
+    /// as formulae have always been displayed
+    #[default]
+    CarbonFirst,
+    /// Hill notation, with carbon and hydrogen first followed by everything else
+    /// alphabetically if there is any carbon, or everything alphabetically if not
+    Hill,
+    /// Everything alphabetically
+    Alphabetical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How a [`FormulaFormatter`] writes counts and charges
+pub enum SubscriptStyle {
+    /// Inline, like `C6H12O6+2`, which can be read back by [`parse_charged_formula`]
+    #[default]
+    Plain,
+    /// With Unicode subscript and superscript digits, like `C₆H₁₂O₆²⁺`
+    Unicode,
+    /// With HTML `<sub>` and `<sup>` tags, like `C<sub>6</sub>H<sub>12</sub>O<sub>6</sub><sup>2+</sup>`
+    Html,
+    /// With LaTeX subscripts and superscripts, like `C_{6}H_{12}O_{6}^{2+}`
+    Latex,
+}
+
+impl SubscriptStyle {
+    fn write_subscript(&self, result: &mut String, text: &str) {
+        match self {
+            Self::Plain => result.push_str(text),
+            Self::Unicode => result.extend(text.chars().map(|c| match c {
+                '-' => '₋',
+                '+' => '₊',
+                c => char::from_u32('₀' as u32 + c.to_digit(10).unwrap_or(0)).unwrap(),
+            })),
+            Self::Html => {
+                result.push_str("<sub>");
+                result.push_str(text);
+                result.push_str("</sub>");
+            }
+            Self::Latex => {
+                result.push_str("_{");
+                result.push_str(text);
+                result.push('}');
+            }
+        }
+    }
+
+    fn write_superscript(&self, result: &mut String, text: &str) {
+        match self {
+            Self::Plain => result.push_str(text),
+            Self::Unicode => result.extend(text.chars().map(|c| match c {
+                '-' => '⁻',
+                '+' => '⁺',
+                '1' => '¹',
+                '2' => '²',
+                '3' => '³',
+                c => char::from_u32('⁰' as u32 + c.to_digit(10).unwrap_or(0)).unwrap(),
+            })),
+            Self::Html => {
+                result.push_str("<sup>");
+                result.push_str(text);
+                result.push_str("</sup>");
+            }
+            Self::Latex => {
+                result.push_str("^{");
+                result.push_str(text);
+                result.push('}');
+            }
+        }
+    }
+
+    fn write_charge(&self, result: &mut String, charge: i32) {
+        let sign = if charge > 0 { '+' } else { '-' };
+        let magnitude = charge.unsigned_abs();
+        match (self, magnitude) {
+            (_, 0) => {}
+            (Self::Plain, 1) => result.push(sign),
+            (Self::Plain, _) => result.push_str(&format!("{sign}{magnitude}")),
+            (_, 1) => self.write_superscript(result, &sign.to_string()),
+            (_, _) => self.write_superscript(result, &format!("{magnitude}{sign}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/**
+Writes compositions as formulae, in the style configured by its builder methods.
+The default style is the one used by the [`Display`] implementations of the
+composition types.
+
+```rust
+# use chemical_elements::{parse_formula, FormulaFormatter, SubscriptStyle};
+let glucose = parse_formula("C6H12O6").unwrap();
+assert_eq!(FormulaFormatter::new().format(&glucose), "C6H12O6");
+
+let ammonium = parse_formula("NH4").unwrap();
+assert_eq!(FormulaFormatter::hill().charge(1).format(&ammonium), "H4N+");
+assert_eq!(
+    FormulaFormatter::hill()
+        .subscripts(SubscriptStyle::Unicode)
+        .charge(1)
+        .format(&ammonium),
+    "H₄N⁺"
+);
+```
+*/
+pub struct FormulaFormatter {
+    order: ElementOrder,
+    omit_ones: bool,
+    isotopes: IsotopeNotation,
+    group_isotopes: bool,
+    subscripts: SubscriptStyle,
+    charge: i32,
+}
+
+impl FormulaFormatter {
+    /// A formatter writing every count, with carbon and hydrogen first and fixed
+    /// isotopes after their element symbols
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A formatter writing strict Hill notation, omitting counts of one, and with
+    /// fixed isotopes following the natural form of their element
+    pub fn hill() -> Self {
+        Self::new()
+            .order(ElementOrder::Hill)
+            .omit_ones(true)
+            .group_isotopes(true)
+    }
+
+    /// Set the order in which elements are written
+    pub fn order(mut self, order: ElementOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Set whether counts of one are omitted, like the `N` of `C5H5N`
+    pub fn omit_ones(mut self, omit_ones: bool) -> Self {
+        self.omit_ones = omit_ones;
+        self
+    }
+
+    /// Set how elements with a fixed isotope are written
+    pub fn isotopes(mut self, notation: IsotopeNotation) -> Self {
+        self.isotopes = notation;
+        self
+    }
+
+    /// Set whether fixed isotopes are written immediately after the natural form
+    /// of their element, like `CC[13]2H6`, rather than being placed alphabetically
+    /// after carbon and hydrogen, like `CH6C[13]2`
+    pub fn group_isotopes(mut self, group_isotopes: bool) -> Self {
+        self.group_isotopes = group_isotopes;
+        self
+    }
+
+    /// Set how counts and the charge are written
+    pub fn subscripts(mut self, style: SubscriptStyle) -> Self {
+        self.subscripts = style;
+        self
+    }
+
+    /// Set the charge written after the formula, which is omitted if it is zero. A
+    /// formula with negative counts is enclosed in brackets before its charge, like
+    /// `[H-2O-1]-`, so that the charge can't be read as a count.
+    pub fn charge(mut self, charge: i32) -> Self {
+        self.charge = charge;
+        self
+    }
+
+    /// The position of an element in the formula, which is sorted by these keys
+    fn sort_key<'a>(
+        &self,
+        key: &'a ElementSpecification,
+        carbon_first: bool,
+    ) -> (bool, u8, &'a str, u16) {
+        let prefixed = self.isotopes == IsotopeNotation::Prefix && key.isotope != 0;
+        let symbol = key.element.symbol.as_str();
+        let rank = match symbol {
+            "C" | "H" if carbon_first && (key.isotope == 0 || self.group_isotopes) => {
+                if symbol == "C" {
+                    0
+                } else {
+                    1
+                }
+            }
+            _ => 2,
+        };
+        (!prefixed, rank, symbol, key.isotope)
+    }
+
+    fn write_element(&self, result: &mut String, key: &ElementSpecification, count: i32) {
+        let symbol = &key.element.symbol;
+        let isotope = key.isotope;
+        match (isotope, self.isotopes) {
+            (0, _) => result.push_str(symbol),
+            (2, IsotopeNotation::HydrogenSymbols) if symbol == "H" => result.push('D'),
+            (3, IsotopeNotation::HydrogenSymbols) if symbol == "H" => result.push('T'),
+            (_, IsotopeNotation::Suffix | IsotopeNotation::HydrogenSymbols) => {
+                result.push_str(&format!("{symbol}[{isotope}]"))
+            }
+            (_, IsotopeNotation::BracketPrefix) => result.push_str(&format!("[{isotope}{symbol}]")),
+            (_, IsotopeNotation::Caret) => result.push_str(&format!("^{isotope}{symbol}")),
+            (_, IsotopeNotation::Prefix) => {
+                self.subscripts
+                    .write_superscript(result, &isotope.to_string());
+                result.push_str(symbol);
+            }
         }
+        if !(self.omit_ones && count == 1) {
+            self.subscripts.write_subscript(result, &count.to_string());
+        }
+    }
+
+    /// Write `composition` as a formula
+    pub fn format<'inner, 'lifespan: 'inner, C>(&self, composition: &'inner C) -> String
+    where
+        &'inner C: Into<ChemicalCompositionRef<'inner, 'lifespan>> + 'inner,
+    {
+        let composition: ChemicalCompositionRef<'inner, 'lifespan> = composition.into();
+        let mut items: Vec<(&ElementSpecification, i32)> = composition
+            .iter()
+            .filter(|(_, count)| **count != 0)
+            .map(|(key, count)| (key, *count))
+            .collect();
+        let carbon_first = match self.order {
+            ElementOrder::CarbonFirst => true,
+            ElementOrder::Hill => items.iter().any(|(key, _)| key.element.symbol == "C"),
+            ElementOrder::Alphabetical => false,
+        };
+        items.sort_by(|a, b| {
+            self.sort_key(a.0, carbon_first)
+                .cmp(&self.sort_key(b.0, carbon_first))
+        });
+
+        let bracketed = self.charge != 0 && items.iter().any(|(_, count)| *count < 0);
+        let mut result = String::with_capacity(composition.len() * 2 + 2);
+        if bracketed {
+            result.push('[');
+        }
+        for (key, count) in items {
+            self.write_element(&mut result, key, count);
+        }
+        if bracketed {
+            result.push(']');
+        }
+        self.subscripts.write_charge(&mut result, self.charge);
+        result
     }
-    result
+}
+
+/// Write `composition` as a formula in the default style of [`FormulaFormatter`],
+/// which is used by the [`Display`] implementations of the composition types
+pub fn to_formula<'inner, 'lifespan: 'inner, C>(composition: &'inner C) -> String
+where
+    &'inner C: Into<ChemicalCompositionRef<'inner, 'lifespan>> + 'inner,
+{
+    FormulaFormatter::new().format(composition)
+}
+
+/// Write `composition` as a formula, writing any fixed isotopes with `notation`
+pub fn to_formula_with_notation<'inner, 'lifespan: 'inner, C>(
+    composition: &'inner C,
+    notation: IsotopeNotation,
+) -> String
+where
+    &'inner C: Into<ChemicalCompositionRef<'inner, 'lifespan>> + 'inner,
+{
+    FormulaFormatter::new()
+        .isotopes(notation)
+        .format(composition)
 }
 
 #[cfg(test)]
@@ -433,4 +1462,415 @@ mod test {
         let res = FormulaParser::parse("H12O6C6N2").unwrap();
         assert_eq!(res.to_string(), "C6H12N2O6");
     }
+
+    #[test]
+    fn test_formatter() {
+        let pyridine = parse_formula("C5H5N").unwrap();
+        assert_eq!(pyridine.to_string(), "C5H5N1");
+        assert_eq!(FormulaFormatter::hill().format(&pyridine), "C5H5N");
+
+        let sulfate = parse_formula("SO4").unwrap();
+        let hill = FormulaFormatter::hill().charge(-2);
+        assert_eq!(hill.format(&sulfate), "O4S-2");
+        for (style, formula) in [
+            (SubscriptStyle::Unicode, "O₄S²⁻"),
+            (SubscriptStyle::Html, "O<sub>4</sub>S<sup>2-</sup>"),
+            (SubscriptStyle::Latex, "O_{4}S^{2-}"),
+        ] {
+            assert_eq!(hill.subscripts(style).format(&sulfate), formula);
+        }
+
+        let water = parse_formula("H2O").unwrap();
+        assert_eq!(FormulaFormatter::new().format(&water), "H2O1");
+        assert_eq!(FormulaFormatter::hill().format(&water), "H2O");
+        let chloroform = parse_formula("CHCl3").unwrap();
+        assert_eq!(FormulaFormatter::hill().format(&chloroform), "CHCl3");
+        assert_eq!(
+            FormulaFormatter::hill()
+                .order(ElementOrder::Alphabetical)
+                .format(&chloroform),
+            "CCl3H"
+        );
+        let salt = parse_formula("HNaCl").unwrap();
+        assert_eq!(FormulaFormatter::new().format(&salt), "H1Cl1Na1");
+        assert_eq!(FormulaFormatter::hill().format(&salt), "ClHNa");
+
+        let labeled = parse_formula("CC[13]2H5D").unwrap();
+        assert_eq!(FormulaFormatter::new().format(&labeled), "C1H5C[13]2H[2]1");
+        assert_eq!(FormulaFormatter::hill().format(&labeled), "CC[13]2H5H[2]");
+        assert_eq!(
+            FormulaFormatter::hill()
+                .isotopes(IsotopeNotation::Prefix)
+                .subscripts(SubscriptStyle::Unicode)
+                .format(&labeled),
+            "¹³C₂²HCH₅"
+        );
+        let formula = FormulaFormatter::hill()
+            .isotopes(IsotopeNotation::HydrogenSymbols)
+            .charge(1)
+            .format(&labeled);
+        assert_eq!(formula, "CC[13]2H5D+");
+        assert_eq!(
+            parse_charged_formula(&formula).unwrap(),
+            (labeled.clone(), 1)
+        );
+
+        let mut zeros = parse_formula("C6H12O6").unwrap();
+        zeros["N"] = 0;
+        assert_eq!(zeros.to_string(), "C6H12O6");
+    }
+
+    #[test]
+    fn test_charged() {
+        let (res, charge) = parse_charged_formula("C6H13O6+").unwrap();
+        assert_eq!(charge, 1);
+        assert_eq!(res["H"], 13);
+
+        let (res, charge) = parse_charged_formula("SO4-2").unwrap();
+        assert_eq!(charge, -2);
+        assert_eq!(res["S"], 1);
+        assert_eq!(res["O"], 4);
+
+        let (res, charge) = parse_charged_formula("Fe+3").unwrap();
+        assert_eq!(charge, 3);
+        assert_eq!(res["Fe"], 1);
+
+        let (res, charge) = parse_charged_formula("[C6H12O6]2+").unwrap();
+        assert_eq!(charge, 2);
+        assert_eq!(res["C"], 6);
+
+        let (res, charge) = parse_charged_formula("(CH3)2N--").unwrap();
+        assert_eq!(charge, -2);
+        assert_eq!(res["H"], 6);
+
+        let (res, charge) = parse_charged_formula("H2O").unwrap();
+        assert_eq!(charge, 0);
+        assert_eq!(res, parse_formula("H2O").unwrap());
+
+        for formula in ["C6H13O6+", "SO4-2", "Fe+3", "[C6H12O6]2+", "PO4-3"] {
+            let (res, charge) = parse_charged_formula(formula).unwrap();
+            let (dup, dup_charge) =
+                parse_charged_formula(&to_charged_formula(&res, charge)).unwrap();
+            assert_eq!(res, dup);
+            assert_eq!(charge, dup_charge);
+        }
+        assert_eq!(
+            to_charged_formula(&parse_formula("SO4").unwrap(), -2),
+            "O4S1-2"
+        );
+
+        for formula in [
+            "C6H12O6+-",
+            "[C6H12O6]2+2",
+            "C6H12O6+0",
+            "C6H12O6+a",
+            "[C6H12O6",
+        ] {
+            assert!(parse_charged_formula(formula).is_err(), "{formula}");
+        }
+        assert!(parse_formula("C6H13O6+").is_err());
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let err = parse_formula("C6H12O6)").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::MismatchedBracket);
+        assert_eq!(err.offset, 7);
+        assert_eq!(err.token, ")");
+        assert_eq!(err.opened_at, None);
+        assert_eq!(err.caret_message(), "C6H12O6)\n       ^");
+
+        let err = parse_formula("C6H12(O6").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::IncompleteFormula);
+        assert_eq!(err.offset, 8);
+        assert_eq!(err.opened_at, Some(5));
+        assert_eq!(err.expected, vec!["')'"]);
+
+        let err = parse_formula("C6H12(NXy)2").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::UnknownElement);
+        assert_eq!(err.offset, 7);
+        assert_eq!(err.token, "Xy");
+        assert_eq!(err.input, "C6H12(NXy)2");
+        assert_eq!(err.suggestion.as_deref(), Some("Xe"));
+
+        let err = parse_formula("c6").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::InvalidStart);
+        assert_eq!(err.offset, 0);
+
+        let err = parse_formula("C[1x]").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::IsotopeCountMalformed);
+        assert_eq!(err.offset, 3);
+
+        let err = parse_charged_formula("C6H13O6+x").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::ChargeMalformed);
+        assert_eq!(err.offset, 8);
+
+        let err = parse_formula("HCL").unwrap_err();
+        assert_eq!(err.offset, 1);
+        assert_eq!(err.token, "CL");
+        assert_eq!(err.suggestion.as_deref(), Some("Cl"));
+        assert_eq!(
+            err.to_string(),
+            "Unknown element \"CL\" at offset 1, did you mean \"Cl\"?\nHCL\n ^^"
+        );
+    }
+
+    #[test]
+    fn test_nested_groups() {
+        let res = parse_formula("[Co(NH3)6]Cl3").unwrap();
+        assert_eq!(res, parse_formula("CoN6H18Cl3").unwrap());
+
+        let res = parse_formula("K4{Fe[(CN)2]3}2").unwrap();
+        assert_eq!(res, parse_formula("K4Fe2C12N12").unwrap());
+
+        let res = parse_formula("[Cu(H2O)4]SO4·H2O").unwrap();
+        assert_eq!(res, parse_formula("CuSO9H10").unwrap());
+
+        let res = parse_formula("C[13]2(CH2)2").unwrap();
+        assert_eq!(res["C[13]"], 2);
+        assert_eq!(res["C"], 2);
+
+        let (res, charge) = parse_charged_formula("[Fe(CN)6]4-").unwrap();
+        assert_eq!(charge, -4);
+        assert_eq!(res, parse_formula("FeC6N6").unwrap());
+
+        let err = parse_formula("[Co(NH3]6)Cl3").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::MismatchedBracket);
+        assert_eq!(err.offset, 7);
+        assert_eq!(err.opened_at, Some(3));
+        assert_eq!(err.expected, vec!["')'"]);
+        assert_eq!(
+            err.to_string(),
+            "Mismatched bracket \"]\" at offset 7, opened at offset 3, expected ')'\n\
+             [Co(NH3]6)Cl3\n   ^   ^"
+        );
+
+        let err = parse_formula("{Fe(CN)6").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::IncompleteFormula);
+        assert_eq!(err.opened_at, Some(0));
+        assert_eq!(err.expected, vec!["'}'"]);
+    }
+
+    #[test]
+    fn test_hydrates() {
+        let expected = parse_formula("CuSO9H10").unwrap();
+        for formula in ["CuSO4·5H2O", "CuSO4.5H2O", "CuSO4*5H2O", "5H2O·CuSO4"] {
+            assert_eq!(parse_formula(formula).unwrap(), expected, "{formula}");
+        }
+
+        let res = parse_formula("C2H6O*H2O").unwrap();
+        assert_eq!(res, parse_formula("C2H8O2").unwrap());
+
+        let res = parse_formula("2CaSO4·H2O").unwrap();
+        assert_eq!(res["Ca"], 2);
+        assert_eq!(res["O"], 9);
+
+        let res = parse_formula("(CuSO4·5H2O)2").unwrap();
+        assert_eq!(res["H"], 20);
+
+        let (res, charge) = parse_charged_formula("[Fe(H2O)6·2H2O]3+").unwrap();
+        assert_eq!(charge, 3);
+        assert_eq!(res["H"], 16);
+
+        let err = parse_formula("CuSO4··5H2O").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::InvalidStart);
+        assert_eq!(err.offset, 7);
+
+        let err = parse_formula("CuSO4·5").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::IncompleteFormula);
+        assert_eq!(err.offset, 8);
+
+        let err = parse_formula("0H2O").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::MultiplierMalformed);
+        assert_eq!(err.offset, 0);
+
+        let err = parse_formula("C6H12O6.0H2O").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::MultiplierMalformed);
+        assert_eq!(err.offset, 8);
+    }
+
+    #[test]
+    fn test_isotope_notations() {
+        let expected = parse_formula("C[13]6H12O6").unwrap();
+        for formula in ["[13C]6H12O6", "^13C6H12O6", "H12^13C6O6"] {
+            assert_eq!(parse_formula(formula).unwrap(), expected, "{formula}");
+        }
+
+        let res = parse_formula("C5^13CH12O6").unwrap();
+        assert_eq!(res["C"], 5);
+        assert_eq!(res[&ElementSpecification::parse("C[13]").unwrap()], 1);
+
+        let res = parse_formula("CH3[13C]H2OH").unwrap();
+        assert_eq!(res["C"], 1);
+        assert_eq!(res[&ElementSpecification::parse("C[13]").unwrap()], 1);
+
+        let res = parse_formula("CD3OD").unwrap();
+        assert_eq!(res, parse_formula("CH[2]4O").unwrap());
+        let res = parse_formula("T2O").unwrap();
+        assert_eq!(res[&ElementSpecification::parse("H[3]").unwrap()], 2);
+
+        let (res, charge) = parse_charged_formula("[13C]6H13O6+").unwrap();
+        assert_eq!(res, parse_formula("C[13]6H13O6").unwrap());
+        assert_eq!(charge, 1);
+
+        let labeled = parse_formula("C[13]6H12N[15]2O6").unwrap();
+        for (notation, formula) in [
+            (IsotopeNotation::Suffix, "H12C[13]6N[15]2O6"),
+            (IsotopeNotation::BracketPrefix, "H12[13C]6[15N]2O6"),
+            (IsotopeNotation::Caret, "H12^13C6^15N2O6"),
+        ] {
+            assert_eq!(to_formula_with_notation(&labeled, notation), formula);
+            assert_eq!(parse_formula(formula).unwrap(), labeled);
+        }
+        assert_eq!(
+            to_formula_with_notation(&expected, IsotopeNotation::Prefix),
+            "13C6H12O6"
+        );
+        let deuterated = parse_formula("CD3OD").unwrap();
+        let formula = to_formula_with_notation(&deuterated, IsotopeNotation::HydrogenSymbols);
+        assert_eq!(formula, "C1D4O1");
+        assert_eq!(parse_formula(&formula).unwrap(), deuterated);
+
+        for formula in ["[13C6]H12O6", "^C6", "[13C", "D[2]2O", "^99C"] {
+            assert!(parse_formula(formula).is_err(), "{formula}");
+        }
+    }
+
+    #[test]
+    fn test_bare_prefix_isotopes() {
+        // Leading digits which could be a mass number are rejected as ambiguous
+        for formula in ["13C6H12O6", "2H2O", "2H2O·CuSO4", "12CH4", "15N2"] {
+            let err = parse_formula(formula).unwrap_err();
+            assert_eq!(
+                err.kind,
+                FormulaParserErrorKind::AmbiguousMultiplier,
+                "{formula}"
+            );
+            assert_eq!(err.offset, 0);
+        }
+        assert_eq!(
+            parse_formula("3H2O·CuSO4").unwrap(),
+            parse_formula("H6O3CuSO4").unwrap()
+        );
+        assert_eq!(
+            parse_formula("CuSO4·2H2O").unwrap(),
+            parse_formula("CuSO4H4O2").unwrap()
+        );
+
+        let labeled = parse_formula("C[13]6H12O6").unwrap();
+        assert_eq!(
+            parse_formula_with_bare_isotopes("13C6H12O6").unwrap(),
+            labeled
+        );
+        let formula = to_formula_with_notation(&labeled, IsotopeNotation::Prefix);
+        assert_eq!(parse_formula_with_bare_isotopes(&formula).unwrap(), labeled);
+        assert_eq!(
+            parse_formula_with_bare_isotopes("2H2O").unwrap(),
+            parse_formula("D2O").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_negative_counts() {
+        let res = parse_formula("H-2O-1").unwrap();
+        assert_eq!(res["H"], -2);
+        assert_eq!(res["O"], -1);
+        assert_eq!(res.to_string(), "H-2O-1");
+
+        let res = parse_formula("C2H2O-1N1").unwrap();
+        assert_eq!(res["O"], -1);
+        assert_eq!(res["N"], 1);
+
+        let res = parse_formula("C[13]-6H2(OH)-2").unwrap();
+        assert_eq!(res[&ElementSpecification::parse("C[13]").unwrap()], -6);
+        assert_eq!(res["H"], 0);
+        assert_eq!(res["O"], -2);
+
+        let delta = &parse_formula("C2H2O").unwrap() - &parse_formula("H4O2N").unwrap();
+        let formula = delta.to_string();
+        assert_eq!(formula, "C2H-2N-1O-1");
+        assert_eq!(parse_formula(&formula).unwrap(), delta);
+
+        // A charge after negative counts must follow a bracketed formula
+        let err = parse_charged_formula("H-2O-1").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::AmbiguousCharge);
+        assert_eq!(err.offset, 4);
+        let (res, charge) = parse_charged_formula("[H-2O-1]-").unwrap();
+        assert_eq!(res["H"], -2);
+        assert_eq!(res["O"], -1);
+        assert_eq!(charge, -1);
+
+        let delta = parse_formula("H-2O-1").unwrap();
+        for charge in [-2, -1, 1, 2] {
+            let formula = to_charged_formula(&delta, charge);
+            assert!(formula.starts_with("[H-2O-1]"), "{formula}");
+            let (res, dup_charge) = parse_charged_formula(&formula).unwrap();
+            assert_eq!(res, delta);
+            assert_eq!(dup_charge, charge);
+        }
+        assert_eq!(to_charged_formula(&delta, 0), "H-2O-1");
+
+        for formula in ["H-O", "H-", "H2-3O"] {
+            assert!(parse_formula(formula).is_err(), "{formula}");
+        }
+    }
+
+    #[test]
+    fn test_electron() {
+        let res = parse_formula("Na1e*-1").unwrap();
+        assert_eq!(res["Na"], 1);
+        assert_eq!(res["e*"], -1);
+
+        let res = parse_formula("e*2").unwrap();
+        assert_eq!(res["e*"], 2);
+        let res = parse_formula("(H2O)e*").unwrap();
+        assert_eq!(res["e*"], 1);
+
+        // The `*` of `e*` is not a component separator, but others still are
+        let res = parse_formula("H1e*1*2H2O").unwrap();
+        assert_eq!(res["H"], 5);
+        assert_eq!(res["O"], 2);
+        assert_eq!(res["e*"], 1);
+        assert_eq!(parse_formula("CuSO4*5H2O").unwrap()["O"], 9);
+
+        let adduct: crate::adduct::Adduct = "[M+Na]+".parse().unwrap();
+        let formula = adduct.delta.to_string();
+        assert_eq!(formula, "Na1e*-1");
+        assert_eq!(parse_formula(&formula).unwrap(), adduct.delta);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&adduct.delta).unwrap();
+            let dup: ChemicalComposition = serde_json::from_str(&json).unwrap();
+            assert_eq!(dup, adduct.delta);
+        }
+
+        for formula in ["e", "e2", "Nae*", "e**"] {
+            assert!(parse_formula(formula).is_err(), "{formula}");
+        }
+    }
+
+    #[test]
+    fn test_isotope_count() {
+        let res = parse_formula("C[13]6H12O6").unwrap();
+        let c13 = ElementSpecification::parse("C[13]").unwrap();
+        assert_eq!(res[&c13], 6);
+        assert_eq!(res["H"], 12);
+
+        let err = parse_formula("H2(C[99]O)2").unwrap_err();
+        assert_eq!(err.kind, FormulaParserErrorKind::UnknownIsotope);
+        assert_eq!(err.offset, 5);
+        assert_eq!(err.token, "99");
+
+        let mut parser = FormulaParser {
+            allow_unknown_isotopes: true,
+            ..FormulaParser::default()
+        };
+        let res: ChemicalComposition = parser
+            .parse_formula_with_table_generic("H2(C[99]O)2", &PERIODIC_TABLE)
+            .unwrap();
+        let c99 = ElementSpecification::parse_lenient("C[99]").unwrap();
+        assert_eq!(res[&c99], 2);
+        assert!(res.mass() > 200.0);
+    }
 }
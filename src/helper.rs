@@ -50,7 +50,7 @@ impl<'transient, 'lifespan: 'transient> ChemicalElements<'lifespan> {
     pub fn parse_formula(
         &self,
         string: &'transient str,
-    ) -> Result<ChemicalComposition, FormulaParserError> {
+    ) -> Result<ChemicalComposition<'_>, FormulaParserError> {
         ChemicalComposition::parse_with(string, &self.periodic_table)
     }
 
@@ -58,7 +58,7 @@ impl<'transient, 'lifespan: 'transient> ChemicalElements<'lifespan> {
     pub fn parse_element(
         &self,
         string: &'transient str,
-    ) -> Result<ElementSpecification, ElementSpecificationParsingError> {
+    ) -> Result<ElementSpecification<'_>, ElementSpecificationParsingError> {
         ElementSpecification::parse_with(string, &self.periodic_table)
     }
 }
@@ -4,10 +4,69 @@ pub mod baffling;
 pub mod peak;
 pub mod poisson;
 pub mod convolution;
+pub mod isospec;
+pub mod profile;
+pub mod resolution;
+pub mod scoring;
 
 pub use crate::isotopic_pattern::baffling::{
     isotopic_variants, BafflingRecursiveIsotopicPatternGenerator,
 };
 pub use convolution::isotopic_convolution;
+pub use isospec::{isotopic_fine_structure, Coverage, OrderedIsotopologues};
+pub use profile::{PeakShape, ProfileRenderer, ProfileSpectrum, Sampling};
+pub use resolution::{aggregate_peaks, ResolvingPower};
+pub use scoring::{MissingPeaks, PatternMatch};
 pub use crate::isotopic_pattern::peak::{Peak, PeakList, TheoreticalIsotopicPattern};
 pub use crate::isotopic_pattern::poisson::{poisson_approximate_n_peaks_of, poisson_approximation};
+
+use crate::{ChargedComposition, ChemicalComposition};
+
+/**
+A value which an isotopic pattern can be generated from, like a [`ChemicalComposition`]
+or a [`ChargedComposition`].
+
+Plain compositions use the charge and charge carrier mass passed alongside them. A
+[`ChargedComposition`] ignores those arguments and uses its own charge, and the isotopes
+of its charge carriers are included in the pattern.
+*/
+pub trait IsotopicPatternInput<'lifespan> {
+    /// Produce the composition to generate the pattern of, with the charge
+    /// state and charge carrier mass shift to convert its masses to m/z
+    fn into_pattern_input(
+        self,
+        charge: i32,
+        charge_carrier: f64,
+    ) -> (ChemicalComposition<'lifespan>, i32, f64);
+}
+
+impl<'lifespan, C: Into<ChemicalComposition<'lifespan>>> IsotopicPatternInput<'lifespan> for C {
+    fn into_pattern_input(
+        self,
+        charge: i32,
+        charge_carrier: f64,
+    ) -> (ChemicalComposition<'lifespan>, i32, f64) {
+        (self.into(), charge, charge_carrier)
+    }
+}
+
+impl<'lifespan> IsotopicPatternInput<'lifespan> for &ChargedComposition<'lifespan> {
+    fn into_pattern_input(
+        self,
+        _charge: i32,
+        _charge_carrier: f64,
+    ) -> (ChemicalComposition<'lifespan>, i32, f64) {
+        let (composition, shift) = self.isotopic_parts();
+        (composition, self.charge(), shift)
+    }
+}
+
+impl<'lifespan> IsotopicPatternInput<'lifespan> for ChargedComposition<'lifespan> {
+    fn into_pattern_input(
+        self,
+        charge: i32,
+        charge_carrier: f64,
+    ) -> (ChemicalComposition<'lifespan>, i32, f64) {
+        (&self).into_pattern_input(charge, charge_carrier)
+    }
+}
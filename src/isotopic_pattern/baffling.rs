@@ -5,7 +5,9 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use crate::element::Element;
-use crate::isotopic_pattern::{poisson_approximate_n_peaks_of, Peak, PeakList};
+use crate::isotopic_pattern::{
+    poisson_approximate_n_peaks_of, IsotopicPatternInput, Peak, PeakList,
+};
 use crate::{mass_charge_ratio, ChemicalComposition, ElementSpecification};
 
 use fnv::FnvBuildHasher as RandomState;
@@ -89,7 +91,7 @@ impl PolynomialParameters {
     pub fn isotopic_coefficients(element: &Element, with_mass: bool, accumulator: &mut DVec) {
         let max_isotope_number = element.max_neutron_shift;
         let min_neutron_shift = element.min_neutron_shift;
-        let monoisotopic_number = element.element_number as usize;
+        let monoisotopic_number = element.most_abundant_isotope as usize;
         let n = element.isotopes.len();
 
         for z in min_neutron_shift..max_isotope_number + 1 {
@@ -297,6 +299,107 @@ impl Default for IsotopicConstantsCache<'_> {
     }
 }
 
+/// Whether an element's contribution to an isotopic pattern must be computed exactly
+/// rather than by BRAIN, which only considers natural abundances and ignores isotopes
+/// lighter than the most abundant one
+fn is_label(elt: &ElementSpecification) -> bool {
+    elt.isotope != 0 || elt.element.labeled
+}
+
+/// Separate fixed isotopes and labeled elements from the naturally occurring elements
+/// of a composition
+fn split_labels(
+    composition: ChemicalComposition<'_>,
+) -> (ChemicalComposition<'_>, ChemicalComposition<'_>) {
+    if !composition.iter().any(|(elt, _)| is_label(elt)) {
+        return (composition, ChemicalComposition::new());
+    }
+    let mut natural = ChemicalComposition::new();
+    let mut labels = ChemicalComposition::new();
+    for (elt, cnt) in composition.iter() {
+        if is_label(elt) {
+            labels.inc(*elt, *cnt);
+        } else {
+            natural.inc(*elt, *cnt);
+        }
+    }
+    (natural, labels)
+}
+
+/// A coarse distribution stored as `(mass * probability, probability)` pairs, indexed
+/// by the number of neutrons added to its lightest isotopologue
+type Moments = Vec<(f64, f64)>;
+
+fn convolve_moments(a: &Moments, b: &Moments) -> Moments {
+    if a.is_empty() || b.is_empty() {
+        return Moments::new();
+    }
+    let mut out = vec![(0.0, 0.0); a.len() + b.len() - 1];
+    for (i, (mass_a, prob_a)) in a.iter().copied().enumerate() {
+        for (j, (mass_b, prob_b)) in b.iter().copied().enumerate() {
+            let (mass, prob) = &mut out[i + j];
+            *mass += mass_a * prob_b + mass_b * prob_a;
+            *prob += prob_a * prob_b;
+        }
+    }
+    out
+}
+
+/// The exact coarse distribution of `count` atoms of a labeled element or fixed isotope
+fn label_moments(elt: &ElementSpecification, count: i32) -> Moments {
+    if elt.isotope != 0 || elt.element.isotopes.is_empty() {
+        return vec![(elt.mass() * count as f64, 1.0)];
+    }
+    let lightest = *elt.element.isotopes.keys().min().unwrap() as usize;
+    let heaviest = *elt.element.isotopes.keys().max().unwrap() as usize;
+    let mut unit = vec![(0.0, 0.0); heaviest - lightest + 1];
+    for (k, iso) in elt.element.isotopes.iter() {
+        let (mass, prob) = &mut unit[*k as usize - lightest];
+        *mass += iso.mass * iso.abundance;
+        *prob += iso.abundance;
+    }
+
+    let mut result = vec![(0.0, 1.0)];
+    let mut count = count;
+    while count > 0 {
+        if count & 1 == 1 {
+            result = convolve_moments(&result, &unit);
+        }
+        count >>= 1;
+        if count > 0 {
+            unit = convolve_moments(&unit, &unit);
+        }
+    }
+    result
+}
+
+/// Convolve the neutral coarse peaks of the naturally occurring part of a composition,
+/// in order of neutron count, with the exact distributions of its labeled part
+fn convolve_labels(peaks: PeakList, labels: &ChemicalComposition) -> PeakList {
+    let mut moments: Moments = peaks
+        .iter()
+        .map(|p| (p.mz * p.intensity, p.intensity))
+        .collect();
+    for (elt, cnt) in labels.iter() {
+        moments = convolve_moments(&moments, &label_moments(elt, *cnt));
+    }
+    let start = moments
+        .iter()
+        .position(|(_, prob)| *prob >= 1e-10)
+        .unwrap_or(moments.len());
+    let end = moments
+        .iter()
+        .rposition(|(_, prob)| *prob >= 1e-10)
+        .map_or(start, |i| i + 1);
+    moments[start..end]
+        .iter()
+        .map(|(mass, prob)| Peak {
+            mz: if *prob > 0.0 { mass / prob } else { 0.0 },
+            intensity: *prob,
+        })
+        .collect()
+}
+
 fn max_variants(composition: &ChemicalComposition) -> i32 {
     let acc = composition
         .iter()
@@ -344,6 +447,9 @@ impl<'a> ElementPolynomialMap<'a> {
 #[derive(Debug)]
 pub struct IsotopicDistribution<'lifespan, 'outer> {
     pub composition: ChemicalComposition<'outer>,
+    /// Fixed isotopes and isotopically labeled elements, whose contributions are
+    /// computed exactly and convolved with the pattern of `composition`
+    pub labels: ChemicalComposition<'outer>,
     pub constants: IsotopicConstants<'lifespan>,
     pub order: i32,
     pub average_mass: f64,
@@ -367,8 +473,10 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
     ) -> IsotopicDistribution<'lifespan, 'outer> {
         let order: NumPeaksSpec = order.into();
         let order = order.num_peaks(&composition);
+        let (composition, labels) = split_labels(composition);
 
         let mut inst = IsotopicDistribution {
+            labels,
             constants: IsotopicConstants::new(composition.len()),
             max_variants: max_variants(&composition),
             composition,
@@ -508,7 +616,7 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
         params.elementary_symmetric_polynomial
     }
 
-    fn build_polynomial_map(&self) -> ElementPolynomialMap {
+    fn build_polynomial_map(&self) -> ElementPolynomialMap<'_> {
         let mut power_sum = DVec::new();
         let mut ep_map = ElementPolynomialMap::new(self.composition.len());
 
@@ -556,6 +664,22 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
     }
 
     pub fn isotopic_variants(&self, charge: i32, charge_carrier: f64) -> PeakList {
+        let mut peak_list = self.neutral_variants();
+        if !self.labels.is_empty() {
+            peak_list = convolve_labels(peak_list, &self.labels);
+        }
+        if charge != 0 {
+            for peak in peak_list.iter_mut() {
+                peak.mz = mass_charge_ratio(peak.mz, charge, charge_carrier);
+            }
+        }
+
+        peak_list.sort_by(|a, b| a.mz.partial_cmp(&b.mz).unwrap());
+        peak_list
+    }
+
+    /// The uncharged peaks of the naturally occurring elements, in order of neutron count
+    fn neutral_variants(&self) -> PeakList {
         let probability_vector = self.probability_vector();
         let center_mass_vector = self.center_mass_vector(&probability_vector);
 
@@ -569,14 +693,8 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
             .zip(probability_vector)
             .take(self.order as usize + 1)
         {
-            let adjusted_mz = if charge != 0 {
-                mass_charge_ratio(center_mass_i, charge, charge_carrier)
-            } else {
-                center_mass_i
-            };
-
             let peak = Peak {
-                mz: adjusted_mz,
+                mz: center_mass_i,
                 intensity: intensity_i / total,
             };
 
@@ -594,8 +712,6 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
                 peak_list.push(peak);
             }
         }
-
-        peak_list.sort_by(|a, b| a.mz.partial_cmp(&b.mz).unwrap());
         peak_list
     }
 }
@@ -605,18 +721,24 @@ impl<'lifespan: 'transient, 'transient, 'outer: 'lifespan> IsotopicDistribution<
 /// and number of peaks.
 ///
 /// # Parameters
-/// - `composition`: The chemical composition to compute the isotopic pattern for.
+/// - `composition`: The chemical composition to compute the isotopic pattern for. A
+///   [`ChargedComposition`](crate::ChargedComposition) supplies its own charge and carrier.
 /// - `npeaks`: A value that coerces to [`NumPeaksSpec`] which determines how many isotopic
-///             peaks to generate.
+///   peaks to generate.
 /// - `charge`: The charge state to compute the isotopic pattern in.
 /// - `charge_carrier`: The mass shift of the charge carrier, e.g. the mass of a proton.
-pub fn isotopic_variants<'a, C: Into<ChemicalComposition<'a>>>(
+///
+/// Fixed isotopes like `C[13]` and isotopically [labeled](crate::Element::enriched) elements
+/// are computed exactly and convolved with the pattern of the rest of the composition, so
+/// their under- and over-labeled species add peaks beyond those requested by `npeaks`.
+pub fn isotopic_variants<'a, C: IsotopicPatternInput<'a>>(
     composition: C,
     npeaks: impl Into<NumPeaksSpec>,
     charge: i32,
     charge_carrier: f64,
 ) -> PeakList {
-    let composition = composition.into();
+    let (composition, charge, charge_carrier) =
+        composition.into_pattern_input(charge, charge_carrier);
     let npeaks = npeaks.into().num_peaks(&composition);
 
     let dist = IsotopicDistribution::from_composition(composition, npeaks);
@@ -704,18 +826,19 @@ impl<'lifespan, 'outer: 'lifespan> BafflingRecursiveIsotopicPatternGenerator<'li
     /// # Parameters
     /// - `composition`: The chemical composition to compute the isotopic pattern for.
     /// - `npeaks`: A value that coerces to [`NumPeaksSpec`] which determines how many isotopic
-    ///             peaks to generate.
+    ///   peaks to generate.
     /// - `charge`: The charge state to compute the isotopic pattern in.
     /// - `charge_carrier`: The mass shift of the charge carrier, e.g. the mass of a proton.
     #[inline]
-    pub fn isotopic_variants<C: Into<ChemicalComposition<'outer>>>(
+    pub fn isotopic_variants<C: IsotopicPatternInput<'outer>>(
         &mut self,
         composition: C,
         npeaks: impl Into<NumPeaksSpec>,
         charge: i32,
         charge_carrier: f64,
     ) -> PeakList {
-        let composition = composition.into();
+        let (composition, charge, charge_carrier) =
+            composition.into_pattern_input(charge, charge_carrier);
         let npeaks = npeaks.into().num_peaks(&composition);
         let mut dist = IsotopicDistribution::fill_from_composition(composition, npeaks);
         dist.populate_constants_from_cache(&mut self.parameter_cache);
@@ -746,6 +869,16 @@ mod test {
         assert!((peaks[0].intensity - 0.9226372340115745).abs() < 1e-6)
     }
 
+    #[test]
+    fn test_charged() {
+        let comp = ChemicalComposition::parse("C6H12O6").unwrap();
+        let ion =
+            crate::ChargedComposition::new(comp, 1, crate::ChargeCarrier::sodium()).unwrap();
+        let peaks = isotopic_variants(&ion, 5, 0, PROTON);
+        assert_eq!(peaks.len(), 5);
+        assert!((peaks[0].mz - ion.mz()).abs() < 1e-6);
+    }
+
     #[test]
     fn test_sulfur() {
         let comp = ChemicalComposition::parse("C6H13O5S1H3").unwrap();
@@ -786,4 +919,36 @@ mod test {
         eprintln!("{peaks:?}");
         assert!(!peaks.is_empty())
     }
+
+    #[test]
+    fn test_labeled() {
+        let mut table = crate::PERIODIC_TABLE.clone();
+        table.add(table["C"].enriched("Cx", 13, 0.99).unwrap());
+        let comp = ChemicalComposition::parse_with("Cx6H12O6", &table).unwrap();
+        let peaks = isotopic_variants(comp.clone(), 5, 0, PROTON);
+        let base = peaks
+            .iter()
+            .max_by(|a, b| a.intensity.total_cmp(&b.intensity))
+            .unwrap();
+        assert!((base.mz - comp.mass()).abs() < 1e-3);
+        let ctrl = isotopic_variants(ChemicalComposition::parse("H12O6").unwrap(), 5, 0, PROTON);
+        assert!((base.intensity - 0.99f64.powi(6) * ctrl[0].intensity).abs() < 1e-3);
+        // The under-labeled M-1 species precedes the base peak
+        let under = peaks
+            .iter()
+            .find(|p| (p.mz - (base.mz - 1.00335)).abs() < 1e-2)
+            .unwrap();
+        assert!((under.intensity / base.intensity - 6.0 * 0.01 / 0.99).abs() < 1e-3);
+
+        // A fixed isotope is pure, and only shifts the pattern
+        let fixed = ChemicalComposition::parse("C[13]6H12O6").unwrap();
+        let natural = ChemicalComposition::parse("H12O6").unwrap();
+        let mut generator = BafflingRecursiveIsotopicPatternGenerator::new();
+        let peaks = generator.isotopic_variants(fixed.clone(), 3, 0, PROTON);
+        let ctrl = generator.isotopic_variants(natural.clone(), 3, 0, PROTON);
+        assert_eq!(peaks.len(), ctrl.len());
+        assert!((peaks[0].mz - fixed.mass()).abs() < 1e-6);
+        assert!((peaks[0].intensity - ctrl[0].intensity).abs() < 1e-9);
+        assert!((peaks[1].mz - ctrl[1].mz - (fixed.mass() - natural.mass())).abs() < 1e-6);
+    }
 }
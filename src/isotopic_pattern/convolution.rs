@@ -4,8 +4,8 @@
 
 use std::mem::swap;
 
-use super::{Peak, PeakList, TheoreticalIsotopicPattern};
-use crate::{mass_charge_ratio, ChemicalComposition};
+use super::{IsotopicPatternInput, Peak, PeakList, TheoreticalIsotopicPattern};
+use crate::mass_charge_ratio;
 
 fn convolve_with(
     dist: &[(f64, f64)],
@@ -61,7 +61,8 @@ fn convolve_pow(
 ///
 /// # Parameters
 ///
-/// - `composition`: The chemical composition to compute the isotopic pattern for.
+/// - `composition`: The chemical composition to compute the isotopic pattern for. A
+///   [`ChargedComposition`](crate::ChargedComposition) supplies its own charge and carrier.
 /// - `charge`: The charge state to compute the isotopic pattern in.
 /// - `charge_carrier`: The mass shift of the charge carrier, e.g. the mass of a proton.
 /// - `abundance_threshold`: The minimum abundance of an isotopologue to consider for inclusion.
@@ -69,6 +70,10 @@ fn convolve_pow(
 ///                          final peak list after normalization.
 /// # Notes
 ///
+/// Elements with a fixed isotope, like `C[13]`, are treated as isotopically pure. Partially
+/// enriched labels can be described by elements derived with
+/// [`Element::enriched`](crate::Element::enriched).
+///
 /// This method will generate isotopic fine structure, which means that
 /// it will contain many, many low abundance peaks corresponding to isotopomers
 /// of each isotopologue.
@@ -80,19 +85,31 @@ not(feature = "doc-only"),
 doc = "**Doc only not enabled**. Compile with feature `doc-only` and Rust version >= 1.54 \
            to enable."
 )]
-pub fn isotopic_convolution<'a, C: Into<ChemicalComposition<'a>>>(
+pub fn isotopic_convolution<'a, C: IsotopicPatternInput<'a>>(
     composition: C,
     charge: i32,
     charge_carrier: f64,
     abundance_threshold: f64,
 ) -> PeakList {
-    let composition: ChemicalComposition<'a> = composition.into();
+    let (composition, charge, charge_carrier) =
+        composition.into_pattern_input(charge, charge_carrier);
     let mut buffer = Vec::new();
     let mut out = Vec::new();
     let mut tmp = Vec::new();
     let mut tmp2 = Vec::new();
     for (i, (elt, count)) in composition.iter().enumerate() {
-        buffer.extend(elt.element.isotopes.values().map(|i| (i.mass, i.abundance)));
+        if elt.isotope == 0 {
+            buffer.extend(
+                elt.element
+                    .isotopes
+                    .values()
+                    .filter(|i| i.abundance > 0.0)
+                    .map(|i| (i.mass, i.abundance)),
+            );
+        } else {
+            // A fixed isotope is pure, so it only shifts the pattern
+            buffer.push((elt.mass(), 1.0));
+        }
         convolve_pow(&buffer, *count, &mut tmp, abundance_threshold);
         if i == 0 {
             swap(&mut tmp, &mut out);
@@ -122,7 +139,7 @@ pub fn isotopic_convolution<'a, C: Into<ChemicalComposition<'a>>>(
 
 #[cfg(test)]
 mod test {
-    use crate::PROTON;
+    use crate::{ChargeCarrier, ChargedComposition, ChemicalComposition, PROTON};
 
     use super::*;
 
@@ -132,4 +149,39 @@ mod test {
         let peaks = isotopic_convolution(comp, 0, PROTON, 0.001);
         dbg!(&peaks);
     }
+
+    #[test]
+    fn test_charged() {
+        let comp = ChemicalComposition::parse("C6H12O6").unwrap();
+        let ion =
+            ChargedComposition::new(comp.clone(), 1, ChargeCarrier::sodium()).unwrap();
+        let peaks = isotopic_convolution(&ion, 0, 0.0, 1e-6);
+        assert!((peaks[0].mz - ion.mz()).abs() < 1e-6);
+
+        let ctrl = isotopic_convolution(comp.clone(), 1, PROTON, 1e-6);
+        let ion = ChargedComposition::protonated(comp, 1);
+        let peaks = isotopic_convolution(ion, 0, 0.0, 1e-6);
+        assert!((peaks[0].mz - ctrl[0].mz).abs() < 1e-6);
+        // The extra hydrogen contributes its own isotopes
+        assert!(peaks[0].intensity < ctrl[0].intensity);
+    }
+
+    #[test]
+    fn test_labeled() {
+        let fixed = ChemicalComposition::parse("C[13]2O4").unwrap();
+        let peaks = isotopic_convolution(fixed.clone(), 0, PROTON, 1e-6);
+        let ctrl = isotopic_convolution(ChemicalComposition::parse("O4").unwrap(), 0, PROTON, 1e-6);
+        assert_eq!(peaks.len(), ctrl.len());
+        assert!((peaks[0].mz - fixed.mass()).abs() < 1e-6);
+
+        let mut table = crate::PERIODIC_TABLE.clone();
+        table.add(table["N"].enriched("Nx", 15, 0.985).unwrap());
+        let comp = ChemicalComposition::parse_with("Nx2", &table).unwrap();
+        let peaks = isotopic_convolution(comp.clone(), 0, PROTON, 1e-6);
+        // The isotopomers of the singly labeled species are kept apart
+        assert_eq!(peaks.len(), 4);
+        assert!((peaks[3].mz - comp.mass()).abs() < 1e-6);
+        assert!((peaks[3].intensity - 0.985f64.powi(2)).abs() < 1e-9);
+        assert!((peaks[0].intensity - 0.015f64.powi(2)).abs() < 1e-9);
+    }
 }
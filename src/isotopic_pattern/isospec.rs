@@ -0,0 +1,399 @@
+//! Generate isotopic fine structure by enumerating isotopologues in order of decreasing
+//! probability, after IsoSpec ([Łącki, 2017](https://doi.org/10.1021/acs.analchem.6b01459)).
+//!
+//! Each element's isotopologues follow a multinomial distribution whose configurations
+//! can be walked outwards from its mode, one moved atom at a time, in order of decreasing
+//! probability. Those per-element orderings are then combined lazily, so only the
+//! isotopologues that are actually requested are ever computed. Unlike
+//! [`isotopic_convolution`](super::isotopic_convolution), each isotopologue appears
+//! exactly once with its exact mass, and the memory required grows with the number of
+//! isotopologues produced rather than the number of intermediate combinations.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use fnv::FnvBuildHasher as RandomState;
+
+use super::{IsotopicPatternInput, Peak, PeakList, TheoreticalIsotopicPattern};
+use crate::{mass_charge_ratio, ChemicalComposition};
+
+/// How much of an isotopic distribution to enumerate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coverage {
+    /// Include the most probable isotopologues until their probabilities sum to
+    /// at least this much of the distribution, between 0 and 1
+    TotalProbability(f64),
+    /// Include every isotopologue with at least this probability
+    Threshold(f64),
+}
+
+impl Default for Coverage {
+    fn default() -> Self {
+        Self::TotalProbability(0.999)
+    }
+}
+
+/// An item of a search frontier, ordered by its log probability
+#[derive(Debug, Clone)]
+struct Candidate<T> {
+    log_probability: f64,
+    state: T,
+}
+
+impl<T> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Candidate<T> {}
+
+impl<T> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.log_probability.total_cmp(&other.log_probability)
+    }
+}
+
+/// The isotopologues of many atoms of a single element, produced on demand in order
+/// of decreasing probability
+#[derive(Debug, Clone)]
+struct Marginal {
+    masses: Vec<f64>,
+    log_abundances: Vec<f64>,
+    /// `ln(i!)` for every `i` up to the number of atoms
+    log_factorials: Vec<f64>,
+    frontier: BinaryHeap<Candidate<Vec<u32>>>,
+    visited: HashSet<Vec<u32>, RandomState>,
+    /// The configurations visited so far as `(log probability, mass)` pairs
+    configurations: Vec<(f64, f64)>,
+}
+
+impl Marginal {
+    fn new(isotopes: &[(f64, f64)], count: u32) -> Self {
+        let mut log_factorials = Vec::with_capacity(count as usize + 1);
+        log_factorials.push(0.0);
+        for i in 1..=count {
+            log_factorials.push(log_factorials[i as usize - 1] + (i as f64).ln());
+        }
+        let mut this = Self {
+            masses: isotopes.iter().map(|(mass, _)| *mass).collect(),
+            log_abundances: isotopes
+                .iter()
+                .map(|(_, abundance)| abundance.ln())
+                .collect(),
+            log_factorials,
+            frontier: BinaryHeap::new(),
+            visited: HashSet::default(),
+            configurations: Vec::new(),
+        };
+        let mode = this.find_mode(isotopes, count);
+        this.visited.insert(mode.clone());
+        this.frontier.push(Candidate {
+            log_probability: this.log_probability(&mode),
+            state: mode,
+        });
+        this
+    }
+
+    fn log_probability(&self, config: &[u32]) -> f64 {
+        config.iter().zip(self.log_abundances.iter()).fold(
+            self.log_factorials[self.log_factorials.len() - 1],
+            |acc, (c, p)| acc - self.log_factorials[*c as usize] + (*c as f64) * p,
+        )
+    }
+
+    fn mass(&self, config: &[u32]) -> f64 {
+        config
+            .iter()
+            .zip(self.masses.iter())
+            .map(|(c, m)| *c as f64 * m)
+            .sum()
+    }
+
+    /// Start from the expected counts and climb to the most probable configuration
+    fn find_mode(&self, isotopes: &[(f64, f64)], count: u32) -> Vec<u32> {
+        let mut config: Vec<u32> = isotopes
+            .iter()
+            .map(|(_, abundance)| (abundance * count as f64).floor() as u32)
+            .collect();
+        let assigned: u32 = config.iter().sum();
+        config[0] += count.saturating_sub(assigned);
+
+        let mut best = self.log_probability(&config);
+        loop {
+            let mut improved = false;
+            for i in 0..config.len() {
+                for j in 0..config.len() {
+                    if i == j || config[i] == 0 {
+                        continue;
+                    }
+                    config[i] -= 1;
+                    config[j] += 1;
+                    let score = self.log_probability(&config);
+                    if score > best {
+                        best = score;
+                        improved = true;
+                    } else {
+                        config[i] += 1;
+                        config[j] -= 1;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        config
+    }
+
+    /// Get the `i`th most probable configuration, visiting more of them as needed
+    fn get(&mut self, i: usize) -> Option<(f64, f64)> {
+        while self.configurations.len() <= i {
+            let Candidate {
+                log_probability,
+                state,
+            } = self.frontier.pop()?;
+            for from in 0..state.len() {
+                if state[from] == 0 {
+                    continue;
+                }
+                for to in 0..state.len() {
+                    if from == to {
+                        continue;
+                    }
+                    let mut next = state.clone();
+                    next[from] -= 1;
+                    next[to] += 1;
+                    if !self.visited.contains(&next) {
+                        self.visited.insert(next.clone());
+                        self.frontier.push(Candidate {
+                            log_probability: self.log_probability(&next),
+                            state: next,
+                        });
+                    }
+                }
+            }
+            self.configurations
+                .push((log_probability, self.mass(&state)));
+        }
+        Some(self.configurations[i])
+    }
+}
+
+/**
+An iterator over the isotopologues of a [`ChemicalComposition`] in order of decreasing
+probability, as [`Peak`]s of their neutral masses and probabilities.
+
+Fixed isotopes like `C[13]` are isotopically pure and only shift the masses produced.
+Negative counts, as in a delta formula like `H-2O-1`, have no isotopic distribution
+of their own, so they also only shift the masses, by their monoisotopic mass.
+*/
+#[derive(Debug, Clone)]
+pub struct OrderedIsotopologues {
+    marginals: Vec<Marginal>,
+    mass_shift: f64,
+    frontier: BinaryHeap<Candidate<Vec<usize>>>,
+}
+
+impl OrderedIsotopologues {
+    pub fn new(composition: &ChemicalComposition) -> Self {
+        let mut marginals = Vec::with_capacity(composition.len());
+        let mut mass_shift = 0.0;
+        for (elt, count) in composition.iter() {
+            let isotopes: Vec<(f64, f64)> = elt
+                .element
+                .isotopes
+                .values()
+                .filter(|iso| iso.abundance > 0.0)
+                .map(|iso| (iso.mass, iso.abundance))
+                .collect();
+            if elt.isotope != 0 || isotopes.len() < 2 || *count < 0 {
+                mass_shift += elt.mass() * *count as f64;
+            } else if *count > 0 {
+                marginals.push(Marginal::new(&isotopes, *count as u32));
+            }
+        }
+
+        let mut frontier = BinaryHeap::new();
+        let log_probability = marginals
+            .iter_mut()
+            .map(|m| m.get(0).map(|(log_p, _)| log_p))
+            .sum::<Option<f64>>();
+        if let Some(log_probability) = log_probability {
+            frontier.push(Candidate {
+                log_probability,
+                state: vec![0; marginals.len()],
+            });
+        }
+        Self {
+            marginals,
+            mass_shift,
+            frontier,
+        }
+    }
+}
+
+impl Iterator for OrderedIsotopologues {
+    type Item = Peak;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Candidate {
+            log_probability,
+            state,
+        } = self.frontier.pop()?;
+
+        let mut mass = self.mass_shift;
+        for (marginal, i) in self.marginals.iter().zip(state.iter()) {
+            mass += marginal.configurations[*i].1;
+        }
+
+        // Each combination is reached only from the one that differs by a step
+        // in its first non-zero position, so no combination is visited twice
+        for (j, i) in state.iter().copied().enumerate() {
+            let current = self.marginals[j].configurations[i].0;
+            if let Some((log_p, _)) = self.marginals[j].get(i + 1) {
+                let mut next = state.clone();
+                next[j] += 1;
+                self.frontier.push(Candidate {
+                    log_probability: log_probability - current + log_p,
+                    state: next,
+                });
+            }
+            if i != 0 {
+                break;
+            }
+        }
+
+        Some(Peak {
+            mz: mass,
+            intensity: log_probability.exp(),
+        })
+    }
+}
+
+/// Generate isotopic fine structure from a [`ChemicalComposition`] with the specified
+/// charge state, enumerating isotopologues in order of decreasing probability until
+/// `coverage` is satisfied.
+///
+/// # Parameters
+///
+/// - `composition`: The chemical composition to compute the isotopic pattern for. A
+///   [`ChargedComposition`](crate::ChargedComposition) supplies its own charge and carrier.
+/// - `charge`: The charge state to compute the isotopic pattern in.
+/// - `charge_carrier`: The mass shift of the charge carrier, e.g. the mass of a proton.
+/// - `coverage`: When to stop including isotopologues.
+///
+/// # Notes
+///
+/// The peaks are sorted by m/z and their intensities are the probabilities of each
+/// isotopologue, so their [total](TheoreticalIsotopicPattern::total) is the fraction
+/// of the distribution covered. A [`Coverage::TotalProbability`] of 1 can require
+/// enumerating every isotopologue.
+pub fn isotopic_fine_structure<'a, C: IsotopicPatternInput<'a>>(
+    composition: C,
+    charge: i32,
+    charge_carrier: f64,
+    coverage: Coverage,
+) -> TheoreticalIsotopicPattern {
+    let (composition, charge, charge_carrier) =
+        composition.into_pattern_input(charge, charge_carrier);
+    let mut peaks = PeakList::new();
+    let mut total = 0.0;
+    for peak in OrderedIsotopologues::new(&composition) {
+        if let Coverage::Threshold(threshold) = coverage {
+            if peak.intensity < threshold {
+                break;
+            }
+        }
+        total += peak.intensity;
+        peaks.push(peak);
+        if let Coverage::TotalProbability(target) = coverage {
+            if total >= target {
+                break;
+            }
+        }
+    }
+
+    if charge != 0 {
+        for peak in peaks.iter_mut() {
+            peak.mz = mass_charge_ratio(peak.mz, charge, charge_carrier);
+        }
+    }
+    peaks.sort_by(|a, b| a.mz.total_cmp(&b.mz));
+    let origin = peaks.first().map(|p| p.mz).unwrap_or_default();
+    TheoreticalIsotopicPattern::new(peaks, origin)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ChargeCarrier, ChargedComposition, PROTON};
+
+    #[test]
+    fn test_ordered() {
+        let comp = ChemicalComposition::parse("C2").unwrap();
+        let peaks: Vec<_> = OrderedIsotopologues::new(&comp).collect();
+        assert_eq!(peaks.len(), 3);
+        assert!((peaks[0].intensity - 0.9893f64.powi(2)).abs() < 1e-9);
+        assert!((peaks[1].intensity - 2.0 * 0.9893 * 0.0107).abs() < 1e-9);
+        assert!((peaks[2].intensity - 0.0107f64.powi(2)).abs() < 1e-9);
+        assert!((peaks[2].mz - 2.0 * 13.003355).abs() < 1e-6);
+
+        let comp = ChemicalComposition::parse("C60H122N20O16S2").unwrap();
+        let peaks: Vec<_> = OrderedIsotopologues::new(&comp).take(2000).collect();
+        assert!(peaks
+            .windows(2)
+            .all(|w| w[0].intensity >= w[1].intensity * (1.0 - 1e-9)));
+    }
+
+    #[test]
+    fn test_negative_counts() {
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        let expected: Vec<_> = OrderedIsotopologues::new(&glucose).take(10).collect();
+        for (delta, symbol) in [("N-1", "N"), ("C[13]-1", "C[13]")] {
+            let comp = &glucose + &ChemicalComposition::parse(delta).unwrap();
+            let shift = ChemicalComposition::parse(symbol).unwrap().mass();
+            let peaks: Vec<_> = OrderedIsotopologues::new(&comp).take(10).collect();
+            for (peak, reference) in peaks.iter().zip(expected.iter()) {
+                assert!((peak.mz - (reference.mz - shift)).abs() < 1e-6, "{delta}");
+                assert!(
+                    (peak.intensity - reference.intensity).abs() < 1e-12,
+                    "{delta}"
+                );
+            }
+            assert!((peaks[0].mz - comp.mass()).abs() < 1e-6, "{delta}");
+        }
+    }
+
+    #[test]
+    fn test_coverage() {
+        let comp = ChemicalComposition::parse("C6H12O6").unwrap();
+        let pattern =
+            isotopic_fine_structure(comp.clone(), 0, PROTON, Coverage::TotalProbability(0.99));
+        assert!(pattern.total() >= 0.99);
+        assert!((pattern[0].mz - comp.mass()).abs() < 1e-6);
+        assert!((pattern[0].intensity - 0.9226372).abs() < 1e-4);
+
+        let pattern = isotopic_fine_structure(comp.clone(), 0, PROTON, Coverage::Threshold(0.0));
+        assert!((pattern.total() - 1.0).abs() < 1e-9);
+        let average: f64 = pattern.iter().map(|p| p.mz * p.intensity).sum();
+        assert!((average - comp.average_mass()).abs() < 1e-6);
+
+        let pattern = isotopic_fine_structure(comp.clone(), 0, PROTON, Coverage::Threshold(1e-4));
+        assert!(pattern.iter().all(|p| p.intensity >= 1e-4));
+
+        let ion = ChargedComposition::new(comp, 1, ChargeCarrier::sodium()).unwrap();
+        let pattern = isotopic_fine_structure(&ion, 0, 0.0, Coverage::default());
+        assert!((pattern[0].mz - ion.mz()).abs() < 1e-6);
+
+        let fixed = ChemicalComposition::parse("C[13]6").unwrap();
+        let pattern = isotopic_fine_structure(fixed.clone(), 0, PROTON, Coverage::default());
+        assert_eq!(pattern.len(), 1);
+        assert!((pattern[0].mz - fixed.mass()).abs() < 1e-6);
+    }
+}
@@ -277,12 +277,12 @@ impl TheoreticalIsotopicPattern {
     }
 
     #[inline]
-    pub fn iter(&self) -> TheoreticalIsotopicPatternIter {
+    pub fn iter(&self) -> TheoreticalIsotopicPatternIter<'_> {
         self.peaks.iter()
     }
 
     #[inline]
-    pub fn iter_mut(&mut self) -> TheoreticalIsotopicPatternIterMut {
+    pub fn iter_mut(&mut self) -> TheoreticalIsotopicPatternIterMut<'_> {
         self.peaks.iter_mut()
     }
 }
@@ -0,0 +1,287 @@
+//! Render centroided isotopic patterns into simulated profile spectra.
+//!
+//! A [`ProfileRenderer`] draws each [`Peak`] as a [`PeakShape`] whose width is taken
+//! from a [`ResolvingPower`] model, sampled on an m/z grid, with optional baseline
+//! and noise for producing realistic test data.
+
+use super::{Peak, ResolvingPower};
+
+/// The line shape each centroid is drawn with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeakShape {
+    Gaussian,
+    Lorentzian,
+    /// A mixture of a Lorentzian and a Gaussian of the same width, with the given
+    /// Lorentzian fraction between 0 and 1
+    PseudoVoigt(f64),
+}
+
+impl PeakShape {
+    /// The height of the line shape `offset` m/z away from its apex, relative to
+    /// the apex height, for a peak with full width at half maximum `fwhm`
+    pub fn evaluate(&self, offset: f64, fwhm: f64) -> f64 {
+        let x = offset / fwhm;
+        match self {
+            Self::Gaussian => (-4.0 * std::f64::consts::LN_2 * x * x).exp(),
+            Self::Lorentzian => 1.0 / (1.0 + 4.0 * x * x),
+            Self::PseudoVoigt(eta) => {
+                eta * Self::Lorentzian.evaluate(offset, fwhm)
+                    + (1.0 - eta) * Self::Gaussian.evaluate(offset, fwhm)
+            }
+        }
+    }
+}
+
+/// How the points of a profile spectrum are spaced along the m/z axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    /// Points a constant m/z step apart
+    Uniform(f64),
+    /// A constant number of points per peak width, so that points spread out as the
+    /// peak width grows with m/z, as they do for most instruments
+    PerPeakWidth(f64),
+}
+
+/// A signal sampled on an m/z grid, stored as parallel arrays
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileSpectrum {
+    pub mz: Vec<f64>,
+    pub intensity: Vec<f64>,
+}
+
+impl ProfileSpectrum {
+    pub fn len(&self) -> usize {
+        self.mz.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mz.is_empty()
+    }
+
+    /// Iterate over the `(m/z, intensity)` points of the signal
+    pub fn iter(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.mz.iter().copied().zip(self.intensity.iter().copied())
+    }
+}
+
+/// A small, seedable SplitMix64 generator so simulated noise is reproducible
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `(0, 1]`
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A standard normal value, by the Box-Muller transform
+    fn next_normal(&mut self) -> f64 {
+        let (u, v) = (self.next_f64(), self.next_f64());
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+}
+
+/**
+Render centroided peaks into a [`ProfileSpectrum`].
+
+Each peak is drawn with its apex height equal to its intensity and a width given by the
+[`ResolvingPower`] at its m/z. The signal spans from `extent` peak widths below the first
+peak to `extent` peak widths above the last.
+
+```rust
+use chemical_elements::ChemicalComposition;
+use chemical_elements::isotopic_pattern::{
+    isotopic_variants, PeakShape, ProfileRenderer, ResolvingPower,
+};
+
+let peaks = isotopic_variants(ChemicalComposition::parse("C6H12O6").unwrap(), 0, 1, 1.007);
+let profile = ProfileRenderer::new(ResolvingPower::orbitrap(60_000.0))
+    .shape(PeakShape::PseudoVoigt(0.3))
+    .noise(0.001, 42)
+    .render(&peaks);
+assert!(!profile.is_empty());
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileRenderer {
+    resolving_power: ResolvingPower,
+    shape: PeakShape,
+    sampling: Sampling,
+    extent: f64,
+    baseline: (f64, f64),
+    noise: Option<(f64, u64)>,
+}
+
+impl ProfileRenderer {
+    /// Render Gaussian peaks sampled 10 times per peak width, without baseline or noise
+    pub fn new(resolving_power: ResolvingPower) -> Self {
+        Self {
+            resolving_power,
+            shape: PeakShape::Gaussian,
+            sampling: Sampling::PerPeakWidth(10.0),
+            extent: 4.0,
+            baseline: (0.0, 0.0),
+            noise: None,
+        }
+    }
+
+    pub fn shape(mut self, shape: PeakShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// How many peak widths either side of a peak to draw it over
+    pub fn extent(mut self, extent: f64) -> Self {
+        self.extent = extent;
+        self
+    }
+
+    /// Add a linear baseline of `offset + slope * m/z` to the signal
+    pub fn baseline(mut self, offset: f64, slope: f64) -> Self {
+        self.baseline = (offset, slope);
+        self
+    }
+
+    /// Add normally distributed noise with standard deviation `sd` to the signal,
+    /// drawn reproducibly from `seed`. The noisy signal is clamped to be non-negative.
+    pub fn noise(mut self, sd: f64, seed: u64) -> Self {
+        self.noise = Some((sd, seed));
+        self
+    }
+
+    fn grid(&self, start: f64, end: f64) -> Vec<f64> {
+        let mut grid = Vec::new();
+        let mut mz = start;
+        while mz <= end {
+            grid.push(mz);
+            let step = match self.sampling {
+                Sampling::Uniform(step) => step,
+                Sampling::PerPeakWidth(n) => self.resolving_power.fwhm(mz) / n,
+            };
+            if !(step > 0.0 && step.is_finite()) {
+                break;
+            }
+            mz += step;
+        }
+        grid
+    }
+
+    /// Render `peaks` into a profile spectrum. The peaks need not be sorted.
+    pub fn render(&self, peaks: &[Peak]) -> ProfileSpectrum {
+        let (Some(lowest), Some(highest)) = (
+            peaks.iter().map(|p| p.mz).min_by(f64::total_cmp),
+            peaks.iter().map(|p| p.mz).max_by(f64::total_cmp),
+        ) else {
+            return ProfileSpectrum::default();
+        };
+        let start = lowest - self.extent * self.resolving_power.fwhm(lowest);
+        let end = highest + self.extent * self.resolving_power.fwhm(highest);
+        let mz = self.grid(start, end);
+
+        let (offset, slope) = self.baseline;
+        let mut intensity: Vec<f64> = mz.iter().map(|x| slope.mul_add(*x, offset)).collect();
+        for peak in peaks {
+            let fwhm = self.resolving_power.fwhm(peak.mz);
+            let lo = mz.partition_point(|x| *x < peak.mz - self.extent * fwhm);
+            let hi = mz.partition_point(|x| *x <= peak.mz + self.extent * fwhm);
+            for (x, y) in mz[lo..hi].iter().zip(intensity[lo..hi].iter_mut()) {
+                *y += peak.intensity * self.shape.evaluate(x - peak.mz, fwhm);
+            }
+        }
+
+        if let Some((sd, seed)) = self.noise {
+            let mut rng = SplitMix64(seed);
+            for y in intensity.iter_mut() {
+                *y = sd.mul_add(rng.next_normal(), *y).max(0.0);
+            }
+        }
+        ProfileSpectrum { mz, intensity }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn half_width(profile: &ProfileSpectrum) -> f64 {
+        let half = profile.intensity.iter().copied().fold(0.0, f64::max) / 2.0;
+        let above: Vec<_> = profile.iter().filter(|(_, y)| *y >= half).collect();
+        above.last().unwrap().0 - above[0].0
+    }
+
+    #[test]
+    fn test_shapes() {
+        assert!((PeakShape::Gaussian.evaluate(0.05, 0.1) - 0.5).abs() < 1e-12);
+        assert!((PeakShape::Lorentzian.evaluate(-0.05, 0.1) - 0.5).abs() < 1e-12);
+        assert!((PeakShape::PseudoVoigt(0.5).evaluate(0.05, 0.1) - 0.5).abs() < 1e-12);
+        assert!(PeakShape::Lorentzian.evaluate(0.3, 0.1) > PeakShape::Gaussian.evaluate(0.3, 0.1));
+    }
+
+    #[test]
+    fn test_render() {
+        let peaks = [Peak {
+            mz: 500.0,
+            intensity: 2.0,
+        }];
+        let renderer = ProfileRenderer::new(ResolvingPower::ConstantWidth(0.1))
+            .sampling(Sampling::Uniform(0.001));
+        let profile = renderer.render(&peaks);
+        let apex = profile.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        assert!((apex.0 - 500.0).abs() < 1e-3);
+        assert!((apex.1 - 2.0).abs() < 1e-3);
+        assert!((profile.mz[0] - 499.6).abs() < 1e-9);
+        assert!((half_width(&profile) - 0.1).abs() < 0.01);
+
+        // Points spread out with the peak width
+        let renderer = ProfileRenderer::new(ResolvingPower::orbitrap(60_000.0));
+        let profile = renderer.render(&[
+            Peak {
+                mz: 200.0,
+                intensity: 1.0,
+            },
+            Peak {
+                mz: 1800.0,
+                intensity: 1.0,
+            },
+        ]);
+        let n = profile.len();
+        assert!(profile.mz[n - 1] - profile.mz[n - 2] > 10.0 * (profile.mz[1] - profile.mz[0]));
+
+        assert!(renderer.render(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_baseline_noise() {
+        let peaks = [Peak {
+            mz: 500.0,
+            intensity: 1.0,
+        }];
+        let renderer = ProfileRenderer::new(ResolvingPower::Constant(50_000.0)).baseline(0.1, 0.0);
+        let profile = renderer.render(&peaks);
+        assert!((profile.intensity[0] - 0.1).abs() < 1e-3);
+
+        let noisy = renderer.noise(0.01, 7).render(&peaks);
+        assert_eq!(noisy, renderer.noise(0.01, 7).render(&peaks));
+        assert_ne!(noisy, renderer.noise(0.01, 8).render(&peaks));
+        let deviation = noisy
+            .iter()
+            .zip(profile.iter())
+            .map(|(a, b)| (a.1 - b.1).powi(2))
+            .sum::<f64>()
+            / noisy.len() as f64;
+        assert!((deviation.sqrt() - 0.01).abs() < 0.005);
+    }
+}
@@ -0,0 +1,143 @@
+//! Merge isotopic fine structure into the peaks an instrument of finite resolving
+//! power would observe.
+//!
+//! Fine structure from [`isotopic_convolution`](super::isotopic_convolution) or
+//! [`isotopic_fine_structure`](super::isotopic_fine_structure) lists every isotopologue
+//! separately, while [`isotopic_variants`](super::isotopic_variants) merges them all
+//! into one peak per neutron. [`aggregate_peaks`] models the partially resolved fine
+//! structure in between, given how peak width changes with m/z.
+
+use super::{Peak, PeakList};
+
+/// How the full width at half maximum (FWHM) of a peak depends upon its m/z
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResolvingPower {
+    /// The same FWHM at every m/z
+    ConstantWidth(f64),
+    /// The FWHM is proportional to m/z, a constant resolving power `m/Δm`, as
+    /// for time-of-flight analyzers
+    Constant(f64),
+    /// The FWHM is proportional to m/z^1.5, as for Orbitrap analyzers, given the
+    /// resolving power at a reference m/z
+    Orbitrap { resolution: f64, at_mz: f64 },
+    /// The FWHM is proportional to m/z², as for FT-ICR analyzers, given the
+    /// resolving power at a reference m/z
+    FTICR { resolution: f64, at_mz: f64 },
+}
+
+impl ResolvingPower {
+    /// An Orbitrap analyzer with the given resolving power at m/z 200
+    pub fn orbitrap(resolution: f64) -> Self {
+        Self::Orbitrap {
+            resolution,
+            at_mz: 200.0,
+        }
+    }
+
+    /// An FT-ICR analyzer with the given resolving power at m/z 400
+    pub fn fticr(resolution: f64) -> Self {
+        Self::FTICR {
+            resolution,
+            at_mz: 400.0,
+        }
+    }
+
+    /// The full width at half maximum of a peak at `mz`
+    pub fn fwhm(&self, mz: f64) -> f64 {
+        match self {
+            Self::ConstantWidth(width) => *width,
+            Self::Constant(resolution) => mz / resolution,
+            Self::Orbitrap { resolution, at_mz } => mz.powf(1.5) / (resolution * at_mz.sqrt()),
+            Self::FTICR { resolution, at_mz } => mz.powi(2) / (resolution * at_mz),
+        }
+    }
+
+    /// The resolving power, `m/Δm`, at `mz`
+    pub fn resolution_at(&self, mz: f64) -> f64 {
+        mz / self.fwhm(mz)
+    }
+}
+
+/**
+Merge peaks closer together than the FWHM at their m/z into intensity-weighted centroids,
+as an instrument with the given `resolving_power` would be unable to tell them apart.
+
+The input does not need to be sorted, and the result is sorted by m/z. Merging is repeated
+until every neighbouring pair of centroids is resolved, so the result does not depend upon
+the order the input peaks were listed in.
+*/
+pub fn aggregate_peaks(peaks: &[Peak], resolving_power: ResolvingPower) -> PeakList {
+    let mut current: PeakList = peaks.to_vec();
+    current.sort_by(|a, b| a.mz.total_cmp(&b.mz));
+    loop {
+        let mut merged = PeakList::with_capacity(current.len());
+        for peak in current.iter().copied() {
+            match merged.last_mut() {
+                Some(last)
+                    if peak.mz - last.mz < resolving_power.fwhm((peak.mz + last.mz) / 2.0) =>
+                {
+                    let intensity = last.intensity + peak.intensity;
+                    if intensity > 0.0 {
+                        last.mz = (last.mz * last.intensity + peak.mz * peak.intensity) / intensity;
+                    }
+                    last.intensity = intensity;
+                }
+                _ => merged.push(peak),
+            }
+        }
+        if merged.len() == current.len() {
+            return merged;
+        }
+        current = merged;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::isotopic_pattern::{isotopic_fine_structure, isotopic_variants, Coverage};
+    use crate::{ChemicalComposition, PROTON};
+
+    #[test]
+    fn test_fwhm() {
+        let orbitrap = ResolvingPower::orbitrap(120_000.0);
+        assert!((orbitrap.resolution_at(200.0) - 120_000.0).abs() < 1e-6);
+        assert!((orbitrap.resolution_at(800.0) - 60_000.0).abs() < 1e-6);
+        let fticr = ResolvingPower::fticr(400_000.0);
+        assert!((fticr.resolution_at(800.0) - 200_000.0).abs() < 1e-6);
+        let tof = ResolvingPower::Constant(40_000.0);
+        assert!((tof.fwhm(1000.0) - 0.025).abs() < 1e-9);
+        assert_eq!(ResolvingPower::ConstantWidth(0.1).fwhm(1000.0), 0.1);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let comp = ChemicalComposition::parse("C6H12O6").unwrap();
+        let fine = isotopic_fine_structure(comp.clone(), 1, PROTON, Coverage::Threshold(1e-9));
+
+        // At unit resolution, the fine structure collapses to the coarse pattern
+        let coarse = aggregate_peaks(&fine.peaks, ResolvingPower::ConstantWidth(0.5));
+        let expected = isotopic_variants(comp, coarse.len(), 1, PROTON);
+        for (a, b) in coarse.iter().zip(expected.iter()) {
+            if b.intensity < 1e-6 {
+                break;
+            }
+            assert!((a.mz - b.mz).abs() < 1e-4, "{a} != {b}");
+            assert!((a.intensity - b.intensity).abs() < 1e-4, "{a} != {b}");
+        }
+
+        // The 13C, 17O and 2H isotopologues of M+1 are only resolved at high resolution
+        let m1 = |resolving_power| {
+            aggregate_peaks(&fine.peaks, resolving_power)
+                .iter()
+                .filter(|p| (p.mz - fine[0].mz - 1.0).abs() < 0.1)
+                .count()
+        };
+        assert_eq!(m1(ResolvingPower::orbitrap(500_000.0)), 3);
+        assert_eq!(m1(ResolvingPower::orbitrap(15_000.0)), 1);
+
+        let total: f64 = fine.peaks.iter().map(|p| p.intensity).sum();
+        let merged = aggregate_peaks(&fine.peaks, ResolvingPower::Constant(5_000.0));
+        assert!((merged.iter().map(|p| p.intensity).sum::<f64>() - total).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,333 @@
+//! Score how well experimental peaks fit a theoretical isotopic pattern.
+//!
+//! A [`PatternMatch`] pairs each peak of a [`TheoreticalIsotopicPattern`] with the nearest
+//! experimental peak within a [`MassTolerance`], and then computes similarity scores,
+//! where larger is better, or divergences and test statistics, where smaller is better,
+//! between the two sets of intensities. How theoretical peaks with no experimental
+//! match are treated is controlled by [`MissingPeaks`].
+//!
+//! ```rust
+//! use chemical_elements::ChemicalComposition;
+//! use chemical_elements::decomposition::MassTolerance;
+//! use chemical_elements::isotopic_pattern::{isotopic_variants, TheoreticalIsotopicPattern};
+//! use chemical_elements::isotopic_pattern::scoring::{MissingPeaks, PatternMatch};
+//!
+//! let comp = ChemicalComposition::parse("C6H12O6").unwrap();
+//! let theoretical = TheoreticalIsotopicPattern::from(isotopic_variants(comp, 3, 1, 1.007));
+//! let observed = theoretical.clone().scale_by(1000.0);
+//! let fit = PatternMatch::new(&theoretical, &observed.peaks, MassTolerance::PPM(10.0));
+//! assert!((fit.cosine(MissingPeaks::Zero) - 1.0).abs() < 1e-9);
+//! ```
+
+use super::{Peak, TheoreticalIsotopicPattern};
+use crate::decomposition::MassTolerance;
+
+/// How to score a theoretical peak that has no experimental peak within tolerance
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MissingPeaks {
+    /// Treat the peak as observed with no intensity
+    #[default]
+    Zero,
+    /// Leave the peak out, renormalizing the theoretical pattern over the matched peaks
+    Ignore,
+    /// Treat the peak as observed at a fixed intensity, like the noise level of the spectrum
+    Floor(f64),
+}
+
+/// The peaks of a theoretical isotopic pattern paired with their experimental matches
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternMatch {
+    /// Each theoretical peak with the nearest experimental peak within tolerance, if any
+    pub pairs: Vec<(Peak, Option<Peak>)>,
+    pub tolerance: MassTolerance,
+}
+
+impl PatternMatch {
+    /**
+    Match each peak of `theoretical` to the nearest peak of `experimental` within `tolerance`
+    of its m/z. The experimental peaks need not be sorted, and one experimental peak may be
+    matched by several theoretical peaks, as happens for unresolved fine structure.
+    */
+    pub fn new(
+        theoretical: &TheoreticalIsotopicPattern,
+        experimental: &[Peak],
+        tolerance: MassTolerance,
+    ) -> Self {
+        let pairs = theoretical
+            .iter()
+            .map(|theo| {
+                let (lower, upper) = tolerance.bounds(theo.mz);
+                let hit = experimental
+                    .iter()
+                    .filter(|p| p.mz >= lower && p.mz <= upper)
+                    .min_by(|a, b| (a.mz - theo.mz).abs().total_cmp(&(b.mz - theo.mz).abs()))
+                    .copied();
+                (*theo, hit)
+            })
+            .collect();
+        Self { pairs, tolerance }
+    }
+
+    /// The number of theoretical peaks with no experimental match
+    pub fn missing(&self) -> usize {
+        self.pairs.iter().filter(|(_, hit)| hit.is_none()).count()
+    }
+
+    /// The theoretical and experimental intensities to compare, after handling missing peaks
+    fn intensities(&self, missing: MissingPeaks) -> (Vec<f64>, Vec<f64>) {
+        self.pairs
+            .iter()
+            .filter_map(|(theo, hit)| match (hit, missing) {
+                (Some(hit), _) => Some((theo.intensity, hit.intensity)),
+                (None, MissingPeaks::Zero) => Some((theo.intensity, 0.0)),
+                (None, MissingPeaks::Floor(floor)) => Some((theo.intensity, floor)),
+                (None, MissingPeaks::Ignore) => None,
+            })
+            .unzip()
+    }
+
+    /// Both sets of intensities scaled to sum to 1, or `None` if either has no signal
+    fn distributions(&self, missing: MissingPeaks) -> Option<(Vec<f64>, Vec<f64>)> {
+        let (theo, exp) = self.intensities(missing);
+        let theo_total: f64 = theo.iter().sum();
+        let exp_total: f64 = exp.iter().sum();
+        if theo_total <= 0.0 || exp_total <= 0.0 {
+            return None;
+        }
+        Some((
+            theo.iter().map(|t| t / theo_total).collect(),
+            exp.iter().map(|e| e / exp_total).collect(),
+        ))
+    }
+
+    /// The cosine similarity, or normalized dot product, of the intensities, from 0 to 1
+    pub fn cosine(&self, missing: MissingPeaks) -> f64 {
+        let (theo, exp) = self.intensities(missing);
+        let dot: f64 = theo.iter().zip(exp.iter()).map(|(t, e)| t * e).sum();
+        let norm = theo.iter().map(|t| t * t).sum::<f64>().sqrt()
+            * exp.iter().map(|e| e * e).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            dot / norm
+        } else {
+            0.0
+        }
+    }
+
+    /// The normalized spectral contrast angle, `1 - 2θ/π` where `θ` is the angle between
+    /// the intensity vectors, from 0 for orthogonal patterns to 1 for identical ones
+    pub fn spectral_contrast_angle(&self, missing: MissingPeaks) -> f64 {
+        let angle = self.cosine(missing).clamp(-1.0, 1.0).acos();
+        1.0 - 2.0 * angle / std::f64::consts::PI
+    }
+
+    /// Pearson's chi-square statistic of the experimental intensities against the
+    /// theoretical pattern scaled to the same total, or infinity if nothing was observed
+    pub fn chi_square(&self, missing: MissingPeaks) -> f64 {
+        let (theo, exp) = self.intensities(missing);
+        let (theo_total, exp_total): (f64, f64) = (theo.iter().sum(), exp.iter().sum());
+        if theo_total <= 0.0 || exp_total <= 0.0 {
+            return f64::INFINITY;
+        }
+        theo.iter()
+            .zip(exp.iter())
+            .map(|(t, e)| {
+                let expected = t / theo_total * exp_total;
+                (e - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    /// The G-test statistic, `2 Σ o ln(o / e)`, of the experimental intensities against
+    /// the theoretical pattern scaled to the same total, or infinity if nothing was observed
+    pub fn g_test(&self, missing: MissingPeaks) -> f64 {
+        let (theo, exp) = self.intensities(missing);
+        let (theo_total, exp_total): (f64, f64) = (theo.iter().sum(), exp.iter().sum());
+        if theo_total <= 0.0 || exp_total <= 0.0 {
+            return f64::INFINITY;
+        }
+        2.0 * theo
+            .iter()
+            .zip(exp.iter())
+            .filter(|(_, e)| **e > 0.0)
+            .map(|(t, e)| e * (e / (t / theo_total * exp_total)).ln())
+            .sum::<f64>()
+    }
+
+    /// The Kullback-Leibler divergence of the theoretical pattern from the experimental
+    /// intensities, in nats, or infinity if nothing was observed
+    pub fn kullback_leibler(&self, missing: MissingPeaks) -> f64 {
+        match self.distributions(missing) {
+            Some((theo, exp)) => kullback_leibler(&exp, &theo),
+            None => f64::INFINITY,
+        }
+    }
+
+    /// The Jensen-Shannon divergence between the theoretical pattern and the experimental
+    /// intensities, in nats, from 0 for identical patterns to `ln 2` for disjoint ones
+    pub fn jensen_shannon(&self, missing: MissingPeaks) -> f64 {
+        match self.distributions(missing) {
+            Some((theo, exp)) => {
+                let mean: Vec<f64> = theo
+                    .iter()
+                    .zip(exp.iter())
+                    .map(|(t, e)| (t + e) / 2.0)
+                    .collect();
+                (kullback_leibler(&theo, &mean) + kullback_leibler(&exp, &mean)) / 2.0
+            }
+            None => std::f64::consts::LN_2,
+        }
+    }
+
+    /**
+    The fit score used by MS-Deconv ([Liu, 2010](https://doi.org/10.1074/mcp.M110.002766)),
+    which sums `sqrt(t) * mass accuracy * abundance accuracy` over the theoretical peaks after
+    scaling the theoretical pattern to the experimental intensities, so larger is better.
+
+    Mass accuracy falls linearly from 1 for an exact match to 0 at the edge of the tolerance.
+    Missing peaks contribute nothing, except under [`MissingPeaks::Floor`], where they are
+    scored as a peak at the theoretical m/z with the floor intensity.
+    */
+    pub fn msdeconv(&self, missing: MissingPeaks) -> f64 {
+        let scored: Vec<(Peak, Peak)> = self
+            .pairs
+            .iter()
+            .filter_map(|(theo, hit)| match (hit, missing) {
+                (Some(hit), _) => Some((*theo, *hit)),
+                (None, MissingPeaks::Floor(floor)) => Some((
+                    *theo,
+                    Peak {
+                        mz: theo.mz,
+                        intensity: floor,
+                    },
+                )),
+                (None, _) => None,
+            })
+            .collect();
+        let theo_total: f64 = scored.iter().map(|(theo, _)| theo.intensity).sum();
+        let exp_total: f64 = scored.iter().map(|(_, exp)| exp.intensity).sum();
+        if theo_total <= 0.0 || exp_total <= 0.0 {
+            return 0.0;
+        }
+        let scale = exp_total / theo_total;
+
+        scored
+            .iter()
+            .map(|(theo, exp)| {
+                let width = self.tolerance.width(theo.mz);
+                let mass_accuracy = if width > 0.0 {
+                    (1.0 - (exp.mz - theo.mz).abs() / width).max(0.0)
+                } else {
+                    1.0
+                };
+                let expected = theo.intensity * scale;
+                let observed = exp.intensity;
+                let abundance_accuracy = if observed <= 0.0 {
+                    0.0
+                } else if observed < expected {
+                    (1.0 - (expected - observed) / observed).max(0.0)
+                } else {
+                    (1.0 - (observed - expected) / observed).sqrt()
+                };
+                expected.sqrt() * mass_accuracy * abundance_accuracy
+            })
+            .sum()
+    }
+}
+
+/// `Σ p ln(p / q)` over two distributions of the same length
+fn kullback_leibler(p: &[f64], q: &[f64]) -> f64 {
+    p.iter()
+        .zip(q.iter())
+        .filter(|(p, _)| **p > 0.0)
+        .map(|(p, q)| {
+            if *q > 0.0 {
+                p * (p / q).ln()
+            } else {
+                f64::INFINITY
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::isotopic_pattern::isotopic_variants;
+    use crate::{ChemicalComposition, PROTON};
+
+    fn theoretical() -> TheoreticalIsotopicPattern {
+        let comp = ChemicalComposition::parse("C60H95N15O18").unwrap();
+        TheoreticalIsotopicPattern::from(isotopic_variants(comp, 4, 2, PROTON))
+    }
+
+    fn perturbed(pattern: &TheoreticalIsotopicPattern, noise: &[f64], ppm: f64) -> Vec<Peak> {
+        pattern
+            .iter()
+            .zip(noise.iter())
+            .map(|(p, n)| Peak {
+                mz: p.mz * (1.0 + ppm / 1e6),
+                intensity: p.intensity * 5000.0 * (1.0 + n),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_identical() {
+        let theo = theoretical();
+        let exp = perturbed(&theo, &[0.0; 4], 0.0);
+        let fit = PatternMatch::new(&theo, &exp, MassTolerance::PPM(10.0));
+        let missing = MissingPeaks::Zero;
+        assert_eq!(fit.missing(), 0);
+        assert!((fit.cosine(missing) - 1.0).abs() < 1e-9);
+        assert!((fit.spectral_contrast_angle(missing) - 1.0).abs() < 1e-6);
+        assert!(fit.chi_square(missing).abs() < 1e-9);
+        assert!(fit.g_test(missing).abs() < 1e-9);
+        assert!(fit.kullback_leibler(missing).abs() < 1e-9);
+        assert!(fit.jensen_shannon(missing).abs() < 1e-9);
+        let expected: f64 = exp.iter().map(|p| p.intensity.sqrt()).sum();
+        assert!((fit.msdeconv(missing) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let theo = theoretical();
+        let close = perturbed(&theo, &[0.02, -0.03, 0.05, -0.02], 2.0);
+        let far = perturbed(&theo, &[-0.5, 0.6, 0.4, -0.4], 6.0);
+        let close = PatternMatch::new(&theo, &close, MassTolerance::PPM(10.0));
+        let far = PatternMatch::new(&theo, &far, MassTolerance::PPM(10.0));
+        let missing = MissingPeaks::Zero;
+        assert!(close.cosine(missing) > far.cosine(missing));
+        assert!(close.spectral_contrast_angle(missing) > far.spectral_contrast_angle(missing));
+        assert!(close.chi_square(missing) < far.chi_square(missing));
+        assert!(close.g_test(missing) < far.g_test(missing));
+        assert!(close.kullback_leibler(missing) < far.kullback_leibler(missing));
+        assert!(close.jensen_shannon(missing) < far.jensen_shannon(missing));
+        assert!(close.msdeconv(missing) > far.msdeconv(missing));
+    }
+
+    #[test]
+    fn test_missing() {
+        let theo = theoretical();
+        let mut exp = perturbed(&theo, &[0.0; 4], 0.0);
+        exp.truncate(3);
+        // A peak outside of the tolerance does not match
+        exp.push(Peak {
+            mz: theo[3].mz + 0.1,
+            intensity: 1e4,
+        });
+        let fit = PatternMatch::new(&theo, &exp, MassTolerance::PPM(10.0));
+        assert_eq!(fit.missing(), 1);
+
+        assert!((fit.cosine(MissingPeaks::Ignore) - 1.0).abs() < 1e-9);
+        assert!(fit.cosine(MissingPeaks::Zero) < 1.0);
+        assert!(fit.jensen_shannon(MissingPeaks::Ignore).abs() < 1e-9);
+        assert!(fit.chi_square(MissingPeaks::Zero) > 0.0);
+        assert!(fit.kullback_leibler(MissingPeaks::Floor(1.0)).is_finite());
+        assert!(fit.msdeconv(MissingPeaks::Zero) > 0.0);
+
+        let fit = PatternMatch::new(&theo, &[], MassTolerance::Da(0.01));
+        assert_eq!(fit.cosine(MissingPeaks::Zero), 0.0);
+        assert_eq!(fit.chi_square(MissingPeaks::Zero), f64::INFINITY);
+        assert_eq!(fit.msdeconv(MissingPeaks::Ignore), 0.0);
+    }
+}
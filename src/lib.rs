@@ -31,9 +31,13 @@ assert_eq!(peaks.len(), 6);
 # }
 ```
 */
+pub mod abbreviation;
 mod abstract_composition;
+pub mod adduct;
+mod charged;
 mod composition_list;
 mod composition_map;
+pub mod decomposition;
 mod element;
 mod element_specification;
 mod formula;
@@ -41,15 +45,28 @@ mod helper;
 pub mod isotopic_pattern;
 mod mz;
 mod props;
+pub mod rules;
 mod table;
+mod table_io;
 
 pub use crate::abstract_composition::{ChemicalComposition, ChemicalCompositionRef};
+pub use crate::charged::{ChargeCarrier, ChargeError, ChargedComposition};
 pub use crate::composition_list::ChemicalCompositionVec;
 pub use crate::composition_map::ChemicalCompositionMap;
-pub use crate::element::{Element, Isotope, PeriodicTable};
+pub use crate::element::{Element, Isotope, PeriodicTable, StandardAtomicWeight};
 pub use crate::element_specification::{ElementSpecification, ElementSpecificationParsingError};
-pub use crate::formula::{parse_formula, parse_formula_with_table, FormulaParserError};
-pub use crate::mz::{mass_charge_ratio, neutral_mass, PROTON};
+pub use crate::formula::{
+    parse_charged_formula, parse_charged_formula_with_table, parse_formula,
+    parse_formula_with_bare_isotopes, parse_formula_with_bare_isotopes_with_table,
+    parse_formula_with_table, to_charged_formula, to_formula_with_notation, ElementOrder,
+    FormulaFormatter, FormulaParserError, FormulaParserErrorKind, IsotopeNotation,
+    SubscriptStyle,
+};
+pub use crate::mz::{
+    kendrick_mass, kendrick_mass_defect, mass_charge_ratio, mass_defect, neutral_mass,
+    nominal_mass, KendrickBase, METHYLENE, PROTON,
+};
 pub use crate::table::PERIODIC_TABLE;
+pub use crate::table_io::PeriodicTableError;
 pub use helper::ChemicalElements;
 pub use props::ChemicalCompositionLike;
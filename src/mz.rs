@@ -1,5 +1,10 @@
+use crate::props::ChemicalCompositionLike;
+
 pub const PROTON: f64 = 1.007276;
 
+/// The mass of the methylene repeat unit, CH2, the conventional Kendrick base
+pub const METHYLENE: f64 = 14.01565;
+
 pub fn mass_charge_ratio(neutral_mass: f64, z: i32, charge_carrier: f64) -> f64 {
     let zf: f64 = z as f64;
     (neutral_mass + (zf * charge_carrier)) / zf.abs()
@@ -9,3 +14,151 @@ pub fn neutral_mass(mz: f64, z: i32, charge_carrier: f64) -> f64 {
     let zf: f64 = z as f64;
     (mz * zf.abs()) - (zf * charge_carrier)
 }
+
+/**
+The integer nominal mass of `mass`, rounded to the nearest whole number.
+
+This is not always the same as [`ChemicalCompositionLike::nominal_mass`], which
+sums the mass numbers of the composition's isotopes. Once the accumulated mass
+defect of a composition exceeds 0.5, rounding gives a different nominal mass,
+as for `C60H122` at 842.95, which rounds to 843 but whose mass numbers sum to 842.
+*/
+pub fn nominal_mass(mass: f64) -> i64 {
+    mass.round() as i64
+}
+
+/**
+The difference between `mass` and its [`nominal_mass`], which always lies
+between -0.5 and 0.5.
+
+Like [`nominal_mass`], this can differ from [`ChemicalCompositionLike::mass_defect`]
+for compositions whose accumulated mass defect exceeds 0.5.
+*/
+pub fn mass_defect(mass: f64) -> f64 {
+    mass - mass.round()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/**
+The repeat unit used to re-scale masses onto the Kendrick scale, where the
+repeat unit has an integer mass and members of a homologous series share
+the same Kendrick mass defect.
+
+Any [`ChemicalCompositionLike`] type can be converted into a base, as can a
+bare `f64` mass, with the nominal mass taken from the mass numbers of the
+composition's isotopes or by rounding respectively.
+
+```rust
+# use chemical_elements::{ChemicalComposition, KendrickBase, kendrick_mass_defect};
+let cf2: ChemicalComposition = "CF2".parse().unwrap();
+let base = KendrickBase::from(&cf2);
+assert_eq!(base.nominal_mass, 50.0);
+let kmd = kendrick_mass_defect(498.93, base);
+assert!(kmd.abs() < 0.1);
+```
+*/
+pub struct KendrickBase {
+    /// The exact mass of the repeat unit
+    pub mass: f64,
+    /// The integer mass the repeat unit is scaled to
+    pub nominal_mass: f64,
+}
+
+impl KendrickBase {
+    pub fn new(mass: f64, nominal_mass: f64) -> Self {
+        Self { mass, nominal_mass }
+    }
+
+    /// The factor masses are multiplied by to convert them to the Kendrick scale
+    #[inline]
+    pub fn scale(&self) -> f64 {
+        self.nominal_mass / self.mass
+    }
+}
+
+impl Default for KendrickBase {
+    /// The methylene, CH2, base
+    fn default() -> Self {
+        Self::new(METHYLENE, 14.0)
+    }
+}
+
+impl From<f64> for KendrickBase {
+    fn from(mass: f64) -> Self {
+        Self::new(mass, mass.round())
+    }
+}
+
+impl<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>> From<&C>
+    for KendrickBase
+{
+    fn from(composition: &C) -> Self {
+        Self::new(composition.mass(), composition.nominal_mass() as f64)
+    }
+}
+
+/// Convert `mass` to the Kendrick mass scale defined by `base`
+pub fn kendrick_mass<B: Into<KendrickBase>>(mass: f64, base: B) -> f64 {
+    mass * base.into().scale()
+}
+
+/**
+The Kendrick mass defect of `mass` using the repeat unit `base`, defined as the
+nominal Kendrick mass minus the Kendrick mass, where the nominal Kendrick mass
+is the Kendrick mass rounded to the nearest integer.
+*/
+pub fn kendrick_mass_defect<B: Into<KendrickBase>>(mass: f64, base: B) -> f64 {
+    let km = kendrick_mass(mass, base);
+    km.round() - km
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ChemicalComposition;
+
+    #[test]
+    fn test_nominal_mass() {
+        let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+        assert_eq!(glucose.nominal_mass(), 180);
+        assert_eq!(nominal_mass(glucose.mass()), 180);
+        assert!((glucose.mass_defect() - 0.0633).abs() < 1e-3);
+        assert!((mass_defect(glucose.mass()) - glucose.mass_defect()).abs() < 1e-9);
+
+        let mut labeled = glucose.clone();
+        labeled.set("C".parse().unwrap(), 0);
+        labeled.set("C[13]".parse().unwrap(), 6);
+        assert_eq!(labeled.nominal_mass(), 186);
+
+        // The two definitions part ways once the mass defect exceeds 0.5
+        let alkane = ChemicalComposition::parse("C60H122").unwrap();
+        assert_eq!(alkane.nominal_mass(), 842);
+        assert_eq!(nominal_mass(alkane.mass()), 843);
+        assert!((alkane.mass_defect() - 0.9547).abs() < 1e-3);
+        assert!((mass_defect(alkane.mass()) + 0.0453).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_kendrick_mass() {
+        let ch2 = ChemicalComposition::parse("CH2").unwrap();
+        let base = KendrickBase::from(&ch2);
+        assert!((base.scale() - KendrickBase::default().scale()).abs() < 1e-9);
+        assert!((kendrick_mass(ch2.mass(), base) - 14.0).abs() < 1e-9);
+
+        // Members of a homologous series share a Kendrick mass defect
+        let c16 = ChemicalComposition::parse("C16H32O2").unwrap();
+        let c18 = ChemicalComposition::parse("C18H36O2").unwrap();
+        let kmd16 = c16.kendrick_mass_defect(&ch2);
+        let kmd18 = c18.kendrick_mass_defect(&ch2);
+        assert!((kmd16 - kmd18).abs() < 1e-6);
+        assert!((kmd16 - kendrick_mass_defect(c16.mass(), METHYLENE)).abs() < 1e-6);
+
+        let c2h4o = ChemicalComposition::parse("C2H4O").unwrap();
+        let peg = ChemicalComposition::parse("C2H4O").unwrap() * 10;
+        let peg = &peg + &ChemicalComposition::parse("H2O").unwrap();
+        let peg11 = &peg + &c2h4o;
+        assert!(
+            (peg.kendrick_mass_defect(&c2h4o) - peg11.kendrick_mass_defect(&c2h4o)).abs() < 1e-6
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use crate::abstract_composition::{
@@ -7,6 +9,7 @@ use crate::abstract_composition::{
 use crate::composition_list::ChemicalCompositionVec;
 use crate::composition_map::ChemicalCompositionMap;
 use crate::element_specification::ElementSpecification;
+use crate::mz::{kendrick_mass, kendrick_mass_defect, KendrickBase};
 
 pub trait ChemicalCompositionLike<'inner, 'lifespan: 'inner> {
     /// Access a specific element's count, or `0` if that element is absent
@@ -46,14 +49,183 @@ pub trait ChemicalCompositionLike<'inner, 'lifespan: 'inner> {
         self.mass()
     }
 
+    /**
+    Get the average mass, or molecular weight, of this chemical composition,
+    weighting each element's isotopes by their natural abundance. Elements
+    with a fixed isotope contribute that isotope's mass. If the average mass
+    cache has been populated, return that instead of repeating the calculation.
+    */
+    fn average_mass(&self) -> f64;
+
+    /**
+    Get the average mass of this chemical composition, and cache it,
+    or reuse the cached value. This requires mutability, so this method
+    must be called explicitly.
+    */
+    fn faverage_mass(&mut self) -> f64 {
+        self.average_mass()
+    }
+
+    /**
+    The integer nominal mass of this composition, the sum of the mass numbers
+    of each element's most abundant isotope, or of its fixed isotope when one
+    is specified.
+
+    Unlike [`nominal_mass`](crate::nominal_mass) of a bare mass, this does not
+    round the mass, so it differs from it when the composition's mass defect
+    exceeds 0.5.
+    */
+    fn nominal_mass(&self) -> i64 {
+        self.iter()
+            .map(|(elt_spec, count)| elt_spec.nominal_mass() as i64 * *count as i64)
+            .sum()
+    }
+
+    /// The difference between [`ChemicalCompositionLike::mass`] and [`ChemicalCompositionLike::nominal_mass`],
+    /// which may exceed 0.5 for large compositions, unlike [`mass_defect`](crate::mass_defect)
+    fn mass_defect(&self) -> f64 {
+        self.mass() - self.nominal_mass() as f64
+    }
+
+    /// Convert the mass of this composition to the Kendrick mass scale defined by `base`.
+    /// See [`kendrick_mass`](crate::kendrick_mass).
+    fn kendrick_mass<B: Into<KendrickBase>>(&self, base: B) -> f64
+    where
+        Self: Sized,
+    {
+        kendrick_mass(self.mass(), base)
+    }
+
+    /// The Kendrick mass defect of this composition using the repeat unit `base`.
+    /// See [`kendrick_mass_defect`](crate::kendrick_mass_defect).
+    fn kendrick_mass_defect<B: Into<KendrickBase>>(&self, base: B) -> f64
+    where
+        Self: Sized,
+    {
+        kendrick_mass_defect(self.mass(), base)
+    }
+
+    /// Whether there are no elements stored, including those with a count of zero
     fn is_empty(&self) -> bool;
 
+    /// The number of elements stored, including those with a count of zero
     fn len(&self) -> usize;
 
+    /// Remove the elements whose count is zero
+    fn prune_zeros(&mut self);
+
+    /// Whether no element has a negative count, as in the composition of a
+    /// molecule rather than of a difference between molecules
+    fn is_nonnegative(&self) -> bool {
+        self.iter().all(|(_, v)| *v >= 0)
+    }
+
+    /// The elements with a negative count and their counts, in the order of
+    /// their [`ElementSpecification`]
+    fn negative_elements(&self) -> Vec<(ElementSpecification<'lifespan>, i32)> {
+        let mut negatives: Vec<_> = self
+            .iter()
+            .filter(|(_, v)| **v < 0)
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        negatives.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        negatives
+    }
+
     fn iter(&self) -> AbstractIter<'_, 'lifespan>;
 
     fn iter_mut(&mut self) -> AbstractIterMut<'_, 'lifespan>;
 
+    /*
+    # Set Algebra Methods
+
+    These methods compare compositions element by element, treating absent
+    elements as having a count of zero.
+    */
+
+    /// Whether every element's count is no greater than its count in `other`, as
+    /// the composition of a fragment is to that of its precursor
+    fn is_subset_of<C: ChemicalCompositionLike<'inner, 'lifespan>>(&self, other: &C) -> bool {
+        self.iter().all(|(k, v)| *v <= other.get(k))
+            && other
+                .iter()
+                .all(|(k, v)| *v >= 0 || self.get(k) <= *v)
+    }
+
+    /**
+    The greatest number of copies of `unit` which this composition contains,
+    like the number of repeats of a monomer in a polymer.
+
+    Returns `None` if `unit` has an element with a negative count, or has no
+    element with a positive count.
+    */
+    fn max_multiple_of<C: ChemicalCompositionLike<'inner, 'lifespan>>(
+        &self,
+        unit: &C,
+    ) -> Option<i32> {
+        if unit.iter().any(|(_, v)| *v < 0) {
+            return None;
+        }
+        unit.iter()
+            .filter(|(_, v)| **v > 0)
+            .map(|(k, v)| self.get(k).div_euclid(*v).max(0))
+            .min()
+    }
+
+    /// The greatest common divisor of the element counts, or `0` if there are none
+    fn gcd(&self) -> u32 {
+        fn gcd(a: u32, b: u32) -> u32 {
+            if b == 0 {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+        self.iter().fold(0, |acc, (_, v)| gcd(acc, v.unsigned_abs()))
+    }
+
+    /// The composition divided by the greatest common divisor of its element counts,
+    /// like `CH2O` for `C6H12O6`
+    fn empirical_formula(&self) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        let divisor = self.gcd().max(1) as i64;
+        let mut result = self.clone();
+        let counts: Vec<_> = self.iter().map(|(k, v)| (*k, *v)).collect();
+        for (k, v) in counts {
+            result.set(k, (v as i64 / divisor) as i32);
+        }
+        result.prune_zeros();
+        result
+    }
+
+    /// The lesser of each element's count in this composition and in `other`
+    fn elementwise_min<C: ChemicalCompositionLike<'inner, 'lifespan>>(&self, other: &C) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        let mut result = self.clone();
+        for (k, _) in self.iter().chain(other.iter()) {
+            result.set(*k, self.get(k).min(other.get(k)));
+        }
+        result.prune_zeros();
+        result
+    }
+
+    /// The greater of each element's count in this composition and in `other`
+    fn elementwise_max<C: ChemicalCompositionLike<'inner, 'lifespan>>(&self, other: &C) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        let mut result = self.clone();
+        for (k, _) in self.iter().chain(other.iter()) {
+            result.set(*k, self.get(k).max(other.get(k)));
+        }
+        result.prune_zeros();
+        result
+    }
+
     fn _mul_by(&mut self, scaler: i32) {
         for (_, v) in self.iter_mut() {
             *v *= scaler;
@@ -80,6 +252,14 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.fmass()
     }
 
+    fn average_mass(&self) -> f64 {
+        self.average_mass()
+    }
+
+    fn faverage_mass(&mut self) -> f64 {
+        self.faverage_mass()
+    }
+
     fn is_empty(&self) -> bool {
         self.is_empty()
     }
@@ -88,6 +268,10 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.len()
     }
 
+    fn prune_zeros(&mut self) {
+        self.prune_zeros()
+    }
+
     fn inc(&mut self, elt_spec: ElementSpecification<'lifespan>, count: i32) {
         self.inc(elt_spec, count)
     }
@@ -124,6 +308,14 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.fmass()
     }
 
+    fn average_mass(&self) -> f64 {
+        self.average_mass()
+    }
+
+    fn faverage_mass(&mut self) -> f64 {
+        self.faverage_mass()
+    }
+
     fn is_empty(&self) -> bool {
         self.is_empty()
     }
@@ -132,6 +324,10 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.len()
     }
 
+    fn prune_zeros(&mut self) {
+        self.prune_zeros()
+    }
+
     fn inc(&mut self, elt_spec: ElementSpecification<'lifespan>, count: i32) {
         self.inc(elt_spec, count)
     }
@@ -168,6 +364,14 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.fmass()
     }
 
+    fn average_mass(&self) -> f64 {
+        self.average_mass()
+    }
+
+    fn faverage_mass(&mut self) -> f64 {
+        self.faverage_mass()
+    }
+
     fn is_empty(&self) -> bool {
         self.is_empty()
     }
@@ -176,6 +380,10 @@ impl<'transient, 'lifespan: 'transient> ChemicalCompositionLike<'transient, 'lif
         self.len()
     }
 
+    fn prune_zeros(&mut self) {
+        self.prune_zeros()
+    }
+
     fn inc(&mut self, elt_spec: ElementSpecification<'lifespan>, count: i32) {
         self.inc(elt_spec, count)
     }
@@ -239,6 +447,69 @@ impl_from!(
     AbstractChemicalComposition<'lifespan>
 );
 
+/// The non-zero counts of `composition` ordered by their [`ElementSpecification`],
+/// which are the same for any two equal compositions
+fn canonical_counts<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+    composition: &'inner C,
+) -> Vec<(&'inner ElementSpecification<'lifespan>, i32)> {
+    let mut counts: Vec<_> = composition
+        .iter()
+        .filter(|(_, v)| **v != 0)
+        .map(|(k, v)| (k, *v))
+        .collect();
+    counts.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    counts
+}
+
+/*
+Compositions are compared as if elements with a count of zero were absent. They
+are ordered by comparing their counts element by element in the order of
+[`ElementSpecification`], so that the ordering is the same whatever the storage.
+*/
+macro_rules! impl_comparison {
+    ($tp:ty) => {
+        impl<'lifespan> PartialEq for $tp {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                let contains = |a: &Self, b: &Self| {
+                    ChemicalCompositionLike::iter(a)
+                        .filter(|(_, v)| **v != 0)
+                        .all(|(k, v)| b.get(k) == *v)
+                };
+                contains(self, other) && contains(other, self)
+            }
+        }
+
+        impl<'lifespan> Eq for $tp {}
+
+        impl<'lifespan> Hash for $tp {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                for (k, v) in canonical_counts(self) {
+                    k.hash(state);
+                    v.hash(state);
+                }
+            }
+        }
+
+        impl<'lifespan> PartialOrd for $tp {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<'lifespan> Ord for $tp {
+            fn cmp(&self, other: &Self) -> Ordering {
+                canonical_counts(self).cmp(&canonical_counts(other))
+            }
+        }
+    };
+}
+
+impl_comparison!(ChemicalCompositionMap<'lifespan>);
+impl_comparison!(ChemicalCompositionVec<'lifespan>);
+impl_comparison!(AbstractChemicalComposition<'lifespan>);
+
 macro_rules! impl_arithmetic {
     ($tp:ty) => {
         impl<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>
@@ -468,6 +739,58 @@ impl<'inner, 'lifespan: 'inner> ChemicalCompositionBehavior<'inner, 'lifespan>
 mod test {
     use super::*;
 
+    #[test]
+    fn test_set_algebra() {
+        let glucose = AbstractChemicalComposition::parse("C6H12O6").unwrap();
+        let water = AbstractChemicalComposition::parse("H2O").unwrap();
+        let ammonia = AbstractChemicalComposition::parse("NH3").unwrap();
+        assert!(water.is_subset_of(&glucose));
+        assert!(!ammonia.is_subset_of(&glucose));
+        assert!(AbstractChemicalComposition::new().is_subset_of(&water));
+
+        let loss = AbstractChemicalComposition::parse("H-2O-1").unwrap();
+        assert!(loss.is_subset_of(&water));
+        assert!(!water.is_subset_of(&loss));
+
+        assert_eq!(glucose.max_multiple_of(&water), Some(6));
+        assert_eq!(glucose.max_multiple_of(&ammonia), Some(0));
+        assert_eq!(glucose.max_multiple_of(&loss), None);
+        assert_eq!(glucose.max_multiple_of(&AbstractChemicalComposition::new()), None);
+
+        let polymer = AbstractChemicalComposition::parse("C12H24O12").unwrap();
+        assert_eq!(polymer.gcd(), 12);
+        assert_eq!(
+            polymer.empirical_formula(),
+            AbstractChemicalComposition::parse("CH2O").unwrap()
+        );
+        assert_eq!(
+            glucose.clone().into_map().empirical_formula().to_string(),
+            "C1H2O1"
+        );
+        assert_eq!(AbstractChemicalComposition::new().gcd(), 0);
+
+        let extreme = ChemicalCompositionVec::from(vec![("C", i32::MIN), ("H", i32::MIN)]);
+        assert_eq!(extreme.gcd(), 1 << 31);
+        assert_eq!(
+            extreme.empirical_formula(),
+            ChemicalCompositionVec::from(vec![("C", -1), ("H", -1)])
+        );
+
+        let ethanol = ChemicalCompositionVec::from(vec![("C", 2), ("H", 6), ("O", 1)]);
+        assert_eq!(
+            glucose.elementwise_min(&ethanol),
+            AbstractChemicalComposition::parse("C2H6O").unwrap()
+        );
+        assert_eq!(
+            ammonia.elementwise_max(&water),
+            AbstractChemicalComposition::parse("NH3O").unwrap()
+        );
+        assert_eq!(
+            ammonia.elementwise_min(&water),
+            AbstractChemicalComposition::parse("H2").unwrap()
+        );
+    }
+
     #[test]
     fn test_process() {
         let comp = AbstractChemicalComposition::parse("C6H12O6").unwrap();
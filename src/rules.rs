@@ -0,0 +1,634 @@
+/*!
+Chemical plausibility checks for compositions, such as those produced by
+[`MassDecomposer`](crate::decomposition::MassDecomposer).
+
+Each [`Rule`] checks one property of a composition and reports the
+[`RuleViolation`] it found, and a [`RuleSet`] combines several rules with the
+[`Valences`] they share. [`RuleSet::golden_rules`] builds the heuristic filters
+from [Kind & Fiehn, 2007](https://doi.org/10.1186/1471-2105-8-105).
+
+```rust
+# use chemical_elements::ChemicalComposition;
+use chemical_elements::rules::{RuleSet, RuleViolation};
+
+let rules = RuleSet::golden_rules();
+let glucose = ChemicalComposition::parse("C6H12O6").unwrap();
+assert!(rules.check(&glucose).is_ok());
+
+let radical = ChemicalComposition::parse("C6H11O6").unwrap();
+assert!(matches!(rules.check(&radical), Err(RuleViolation::Lewis { .. })));
+```
+*/
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use fnv::FnvBuildHasher;
+
+use crate::ChemicalCompositionLike;
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+The valence used for each element when computing ring-plus-double-bond
+equivalents and checking the LEWIS and SENIOR rules, keyed by element symbol.
+Fixed isotopes share the valence of their element.
+*/
+pub struct Valences {
+    valences: HashMap<String, i32, FnvBuildHasher>,
+}
+
+impl Default for Valences {
+    /// The lowest common valence of the elements usually found in organic molecules
+    fn default() -> Self {
+        let mut valences = Self::empty();
+        for (symbol, valence) in [
+            ("H", 1),
+            ("B", 3),
+            ("C", 4),
+            ("N", 3),
+            ("O", 2),
+            ("F", 1),
+            ("Na", 1),
+            ("Si", 4),
+            ("P", 3),
+            ("S", 2),
+            ("Cl", 1),
+            ("K", 1),
+            ("Se", 2),
+            ("Br", 1),
+            ("I", 1),
+        ] {
+            valences.set(symbol, valence);
+        }
+        valences
+    }
+}
+
+impl Valences {
+    /// Create a [`Valences`] with no elements
+    pub fn empty() -> Self {
+        Self {
+            valences: HashMap::default(),
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<i32> {
+        self.valences.get(symbol).copied()
+    }
+
+    pub fn set(&mut self, symbol: &str, valence: i32) {
+        self.valences.insert(symbol.to_string(), valence);
+    }
+
+    /// Set the valence for `symbol`, returning `self` for chaining
+    pub fn with(mut self, symbol: &str, valence: i32) -> Self {
+        self.set(symbol, valence);
+        self
+    }
+
+    fn require(&self, symbol: &str) -> Result<i32, RuleViolation> {
+        self.get(symbol)
+            .ok_or_else(|| RuleViolation::UnknownValence {
+                element: symbol.to_string(),
+            })
+    }
+}
+
+/// Sum the counts of each element, merging fixed isotopes into their element
+fn element_counts<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+    composition: &C,
+) -> Vec<(&'lifespan str, i32)> {
+    let mut counts: Vec<(&'lifespan str, i32)> = Vec::new();
+    for (elt_spec, count) in composition.iter() {
+        let symbol = elt_spec.element.symbol.as_str();
+        match counts.iter_mut().find(|(s, _)| *s == symbol) {
+            Some((_, c)) => *c += count,
+            None => counts.push((symbol, *count)),
+        }
+    }
+    counts
+}
+
+fn count_of(counts: &[(&str, i32)], symbol: &str) -> i32 {
+    counts
+        .iter()
+        .find(|(s, _)| *s == symbol)
+        .map(|(_, c)| *c)
+        .unwrap_or(0)
+}
+
+/**
+Compute the ring-plus-double-bond equivalents of `composition`,
+`1 + Σ nᵢ(vᵢ - 2) / 2`, using `valences`.
+
+A [`RuleViolation::UnknownValence`] is returned if an element has no valence.
+*/
+pub fn rdbe<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+    composition: &C,
+    valences: &Valences,
+) -> Result<f64, RuleViolation> {
+    let mut total = 0;
+    for (symbol, count) in element_counts(composition) {
+        total += count * (valences.require(symbol)? - 2);
+    }
+    Ok(1.0 + total as f64 / 2.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The condition of the SENIOR rules which was not met
+pub enum SeniorCondition {
+    /// The sum of valences, or the number of atoms with odd valence, must be even
+    EvenValenceSum,
+    /// The sum of valences must be at least twice the largest valence
+    MaximumValence,
+    /// The sum of valences must be at least twice the number of atoms minus one
+    AtomCount,
+}
+
+impl Display for SeniorCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::EvenValenceSum => "the sum of valences must be even",
+            Self::MaximumValence => {
+                "the sum of valences must be at least twice the largest valence"
+            }
+            Self::AtomCount => {
+                "the sum of valences must be at least twice the number of atoms minus one"
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The rule a composition failed to satisfy, with the values that failed it
+pub enum RuleViolation {
+    /// An element in the composition has no known valence
+    UnknownValence { element: String },
+    /// The composition has a negative count of some element
+    NegativeCount { element: String, count: i32 },
+    /// An element's count fell outside of its permitted range
+    ElementCount {
+        element: String,
+        count: i32,
+        min: i32,
+        max: i32,
+    },
+    /// The ring-plus-double-bond equivalents fell outside of the permitted range
+    RDBE { rdbe: f64, min: f64, max: f64 },
+    /// The parity of the nominal mass does not match the parity of the nitrogen count
+    NitrogenRule { nominal_mass: i64, nitrogen: i32 },
+    /// The ring-plus-double-bond equivalents were not a whole number, so the
+    /// composition cannot be a closed-shell molecule
+    Lewis { rdbe: f64 },
+    /// One of the SENIOR graph-theoretic conditions was not met
+    Senior { condition: SeniorCondition },
+    /// The ratio of two elements' counts fell outside of its permitted range
+    ElementRatio {
+        numerator: String,
+        denominator: String,
+        ratio: f64,
+        min: f64,
+        max: f64,
+    },
+}
+
+impl Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownValence { element } => write!(f, "No valence is known for {element}"),
+            Self::NegativeCount { element, count } => {
+                write!(f, "{element} has a negative count {count}")
+            }
+            Self::ElementCount {
+                element,
+                count,
+                min,
+                max,
+            } => write!(f, "{element} count {count} is outside of [{min}, {max}]"),
+            Self::RDBE { rdbe, min, max } => {
+                write!(f, "RDBE {rdbe} is outside of [{min}, {max}]")
+            }
+            Self::NitrogenRule {
+                nominal_mass,
+                nitrogen,
+            } => write!(
+                f,
+                "Nominal mass {nominal_mass} does not match the parity of {nitrogen} nitrogen atoms"
+            ),
+            Self::Lewis { rdbe } => {
+                write!(
+                    f,
+                    "RDBE {rdbe} is not a whole number, violating the LEWIS rule"
+                )
+            }
+            Self::Senior { condition } => write!(f, "SENIOR rule violated: {condition}"),
+            Self::ElementRatio {
+                numerator,
+                denominator,
+                ratio,
+                min,
+                max,
+            } => write!(
+                f,
+                "{numerator}/{denominator} ratio {ratio} is outside of [{min}, {max}]"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleViolation {}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A single plausibility check on a composition
+pub enum Rule {
+    /// Every element's count must be non-negative
+    NonNegative,
+    /// The count of `element` must fall within `[min, max]`
+    ElementCount { element: String, min: i32, max: i32 },
+    /// The ring-plus-double-bond equivalents must fall within `[min, max]`
+    RDBE { min: f64, max: f64 },
+    /// An odd nominal mass implies an odd number of nitrogen atoms
+    NitrogenRule,
+    /// The ring-plus-double-bond equivalents must be a whole number
+    Lewis,
+    /// The SENIOR rules for the existence of a connected molecular graph
+    Senior,
+    /// The ratio of `numerator` to `denominator` counts must fall within `[min, max]`.
+    /// When neither element is present the rule passes.
+    ElementRatio {
+        numerator: String,
+        denominator: String,
+        min: f64,
+        max: f64,
+    },
+}
+
+impl Rule {
+    /// Create a [`Rule::ElementRatio`] rule relative to carbon
+    pub fn ratio_to_carbon(numerator: &str, min: f64, max: f64) -> Self {
+        Self::ElementRatio {
+            numerator: numerator.to_string(),
+            denominator: "C".to_string(),
+            min,
+            max,
+        }
+    }
+
+    /// Check `composition` against this rule, using `valences` where needed
+    pub fn check<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+        &self,
+        composition: &C,
+        valences: &Valences,
+    ) -> Result<(), RuleViolation> {
+        match self {
+            Self::NonNegative => {
+                for (symbol, count) in element_counts(composition) {
+                    if count < 0 {
+                        return Err(RuleViolation::NegativeCount {
+                            element: symbol.to_string(),
+                            count,
+                        });
+                    }
+                }
+                Ok(())
+            }
+            Self::ElementCount { element, min, max } => {
+                let count = count_of(&element_counts(composition), element);
+                if count < *min || count > *max {
+                    Err(RuleViolation::ElementCount {
+                        element: element.clone(),
+                        count,
+                        min: *min,
+                        max: *max,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            Self::RDBE { min, max } => {
+                let value = rdbe(composition, valences)?;
+                if value < *min || value > *max {
+                    Err(RuleViolation::RDBE {
+                        rdbe: value,
+                        min: *min,
+                        max: *max,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            Self::NitrogenRule => {
+                let nominal_mass = composition.nominal_mass();
+                let nitrogen = count_of(&element_counts(composition), "N");
+                if nominal_mass.rem_euclid(2) != (nitrogen as i64).rem_euclid(2) {
+                    Err(RuleViolation::NitrogenRule {
+                        nominal_mass,
+                        nitrogen,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            Self::Lewis => {
+                let value = rdbe(composition, valences)?;
+                if value.fract() != 0.0 {
+                    Err(RuleViolation::Lewis { rdbe: value })
+                } else {
+                    Ok(())
+                }
+            }
+            Self::Senior => {
+                let mut valence_sum = 0;
+                let mut max_valence = 0;
+                let mut atoms = 0;
+                let mut odd_valence_atoms = 0;
+                for (symbol, count) in element_counts(composition) {
+                    if count == 0 {
+                        continue;
+                    }
+                    let valence = valences.require(symbol)?;
+                    valence_sum += valence * count;
+                    max_valence = max_valence.max(valence);
+                    atoms += count;
+                    if valence % 2 != 0 {
+                        odd_valence_atoms += count;
+                    }
+                }
+                let condition = if valence_sum % 2 != 0 && odd_valence_atoms % 2 != 0 {
+                    Some(SeniorCondition::EvenValenceSum)
+                } else if valence_sum < 2 * max_valence {
+                    Some(SeniorCondition::MaximumValence)
+                } else if valence_sum < 2 * (atoms - 1) {
+                    Some(SeniorCondition::AtomCount)
+                } else {
+                    None
+                };
+                match condition {
+                    Some(condition) => Err(RuleViolation::Senior { condition }),
+                    None => Ok(()),
+                }
+            }
+            Self::ElementRatio {
+                numerator,
+                denominator,
+                min,
+                max,
+            } => {
+                let counts = element_counts(composition);
+                let num = count_of(&counts, numerator);
+                let den = count_of(&counts, denominator);
+                let ratio = match (num, den) {
+                    (0, 0) => return Ok(()),
+                    (_, 0) => f64::INFINITY,
+                    (num, den) => num as f64 / den as f64,
+                };
+                if ratio < *min || ratio > *max {
+                    Err(RuleViolation::ElementRatio {
+                        numerator: numerator.clone(),
+                        denominator: denominator.clone(),
+                        ratio,
+                        min: *min,
+                        max: *max,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// A collection of [`Rule`]s checked in order with shared [`Valences`]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+    pub valences: Valences,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Rule>, valences: Valences) -> Self {
+        Self { rules, valences }
+    }
+
+    /// Add `rule` to the end of the set, returning `self` for chaining
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Replace the [`Valences`] used by the set, returning `self` for chaining
+    pub fn with_valences(mut self, valences: Valences) -> Self {
+        self.valences = valences;
+        self
+    }
+
+    /**
+    The LEWIS and SENIOR checks and the element ratio ranges covering 99.7%
+    of known compounds from the Seven Golden Rules. The remaining rules depend
+    on instrument settings or isotopic pattern data and are not included.
+    */
+    pub fn golden_rules() -> Self {
+        Self::golden_rules_with_ratios(&[
+            ("H", 0.2, 3.1),
+            ("F", 0.0, 1.5),
+            ("Cl", 0.0, 0.8),
+            ("Br", 0.0, 0.8),
+            ("N", 0.0, 1.3),
+            ("O", 0.0, 1.2),
+            ("P", 0.0, 0.3),
+            ("S", 0.0, 0.8),
+            ("Si", 0.0, 0.5),
+        ])
+    }
+
+    /// Like [`RuleSet::golden_rules`], but using the extended element ratio ranges
+    /// which admit rarer compound classes
+    pub fn golden_rules_extended() -> Self {
+        Self::golden_rules_with_ratios(&[
+            ("H", 0.1, 6.0),
+            ("F", 0.0, 6.0),
+            ("Cl", 0.0, 2.0),
+            ("Br", 0.0, 2.0),
+            ("N", 0.0, 4.0),
+            ("O", 0.0, 3.0),
+            ("P", 0.0, 2.0),
+            ("S", 0.0, 3.0),
+            ("Si", 0.0, 1.0),
+        ])
+    }
+
+    fn golden_rules_with_ratios(ratios: &[(&str, f64, f64)]) -> Self {
+        let mut rules = vec![Rule::NonNegative, Rule::Lewis, Rule::Senior];
+        rules.extend(
+            ratios
+                .iter()
+                .map(|(symbol, min, max)| Rule::ratio_to_carbon(symbol, *min, *max)),
+        );
+        Self::new(rules, Valences::default())
+    }
+
+    /// Check `composition` against each rule in order, stopping at the first violation
+    pub fn check<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+        &self,
+        composition: &C,
+    ) -> Result<(), RuleViolation> {
+        for rule in self.rules.iter() {
+            rule.check(composition, &self.valences)?;
+        }
+        Ok(())
+    }
+
+    /// Check `composition` against every rule, collecting all of the violations
+    pub fn violations<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+        &self,
+        composition: &C,
+    ) -> Vec<RuleViolation> {
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.check(composition, &self.valences).err())
+            .collect()
+    }
+
+    /// Test whether `composition` satisfies every rule
+    pub fn is_valid<'inner, 'lifespan: 'inner, C: ChemicalCompositionLike<'inner, 'lifespan>>(
+        &self,
+        composition: &C,
+    ) -> bool {
+        self.check(composition).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decomposition::{MassDecomposer, MassTolerance};
+    use crate::{ChemicalComposition, ChemicalCompositionMap};
+
+    fn parse(s: &str) -> ChemicalComposition<'static> {
+        ChemicalComposition::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_rdbe() {
+        let valences = Valences::default();
+        assert_eq!(rdbe(&parse("C6H6"), &valences).unwrap(), 4.0);
+        assert_eq!(rdbe(&parse("C6H12O6"), &valences).unwrap(), 1.0);
+        assert_eq!(rdbe(&parse("C5H5N"), &valences).unwrap(), 4.0);
+        assert_eq!(rdbe(&parse("C2H5"), &valences).unwrap(), 0.5);
+
+        let phosphate = parse("H3PO4");
+        assert_eq!(rdbe(&phosphate, &valences).unwrap(), 0.0);
+        assert_eq!(
+            rdbe(&phosphate, &Valences::default().with("P", 5)).unwrap(),
+            1.0
+        );
+
+        let map: ChemicalCompositionMap = "C6H6".parse().unwrap();
+        assert_eq!(rdbe(&map, &valences).unwrap(), 4.0);
+
+        assert_eq!(
+            rdbe(&parse("C6H5Fe"), &valences),
+            Err(RuleViolation::UnknownValence {
+                element: "Fe".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_nitrogen_rule() {
+        let rule = Rule::NitrogenRule;
+        let valences = Valences::default();
+        assert!(rule.check(&parse("C5H5N"), &valences).is_ok());
+        assert!(rule.check(&parse("C6H12O6"), &valences).is_ok());
+        assert!(rule.check(&parse("C4H4N2"), &valences).is_ok());
+        assert_eq!(
+            rule.check(&parse("C5H6N"), &valences),
+            Err(RuleViolation::NitrogenRule {
+                nominal_mass: 80,
+                nitrogen: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_lewis_senior() {
+        let valences = Valences::default();
+        assert!(Rule::Lewis.check(&parse("C2H6"), &valences).is_ok());
+        assert!(matches!(
+            Rule::Lewis.check(&parse("C2H5"), &valences),
+            Err(RuleViolation::Lewis { .. })
+        ));
+
+        assert!(Rule::Senior.check(&parse("C2H6"), &valences).is_ok());
+        assert_eq!(
+            Rule::Senior.check(&parse("CH5"), &valences),
+            Err(RuleViolation::Senior {
+                condition: SeniorCondition::EvenValenceSum
+            })
+        );
+        assert_eq!(
+            Rule::Senior.check(&parse("CH6"), &valences),
+            Err(RuleViolation::Senior {
+                condition: SeniorCondition::AtomCount
+            })
+        );
+        assert!(Rule::Senior.check(&parse("C2"), &valences).is_ok());
+        assert_eq!(
+            Rule::Senior.check(&parse("O"), &valences),
+            Err(RuleViolation::Senior {
+                condition: SeniorCondition::MaximumValence
+            })
+        );
+    }
+
+    #[test]
+    fn test_golden_rules() {
+        let rules = RuleSet::golden_rules();
+        assert!(rules.is_valid(&parse("C6H12O6")));
+        assert!(rules.is_valid(&parse("C9H11NO2")));
+        assert!(matches!(
+            rules.check(&parse("CH4O4")),
+            Err(RuleViolation::ElementRatio { ref numerator, .. }) if numerator == "H"
+        ));
+        assert!(RuleSet::golden_rules_extended().is_valid(&parse("CH4O2")));
+
+        let violations = rules.violations(&parse("CH3O3"));
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, RuleViolation::Lewis { .. })));
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, RuleViolation::ElementRatio { .. })));
+
+        let mut composition = parse("C6H12O6");
+        composition.set("H".parse().unwrap(), -2);
+        assert!(matches!(
+            rules.check(&composition),
+            Err(RuleViolation::NegativeCount { .. })
+        ));
+
+        let rules = rules.with_rule(Rule::RDBE { min: 0.0, max: 0.0 });
+        assert!(matches!(
+            rules.check(&parse("C6H10O5")),
+            Err(RuleViolation::RDBE { rdbe, .. }) if rdbe == 2.0
+        ));
+    }
+
+    #[test]
+    fn test_filter_decompositions() {
+        let decomposer = MassDecomposer::new(vec![
+            ("C".parse().unwrap(), (0, 20)),
+            ("H".parse().unwrap(), (0, 40)),
+            ("N".parse().unwrap(), (0, 5)),
+            ("O".parse().unwrap(), (0, 10)),
+        ])
+        .unwrap();
+        let rules = RuleSet::golden_rules().with_rule(Rule::NitrogenRule);
+        let hits = decomposer.decompose(parse("C9H11NO2").mass(), MassTolerance::PPM(20.0));
+        let kept: Vec<_> = hits
+            .iter()
+            .filter(|hit| rules.is_valid(&hit.composition))
+            .collect();
+        assert!(kept.len() < hits.len());
+        assert!(kept.iter().any(|hit| hit.composition == parse("C9H11NO2")));
+    }
+}
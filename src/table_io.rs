@@ -0,0 +1,518 @@
+//! Load a [`PeriodicTable`] at runtime from the NIST isotopic composition listing
+//! or from the JSON layout used to generate the built-in table.
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io::{self, prelude::*};
+
+use crate::element::{Element, Isotope, PeriodicTable, StandardAtomicWeight};
+use crate::table::PERIODIC_TABLE;
+
+/// An error that occurred while reading or writing a [`PeriodicTable`].
+#[derive(Debug)]
+pub enum PeriodicTableError {
+    /// The underlying reader or writer failed
+    IOError(io::Error),
+    /// A record did not contain a required field
+    MissingField { line: usize, field: String },
+    /// A field's value could not be interpreted
+    MalformedValue {
+        line: usize,
+        field: String,
+        value: String,
+    },
+    /// A line could not be split into a `key = value` pair
+    MalformedLine { line: usize, text: String },
+    /// The JSON document was not well formed or did not have the expected layout
+    MalformedJSON(String),
+    /// An element symbol is not in the built-in table, so its atomic number is unknown
+    UnknownElement(String),
+}
+
+impl Display for PeriodicTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(e) => write!(f, "An IO error occurred: {e}"),
+            Self::MissingField { line, field } => {
+                write!(f, "Record ending on line {line} is missing \"{field}\"")
+            }
+            Self::MalformedValue { line, field, value } => {
+                write!(
+                    f,
+                    "Malformed value {value:?} for \"{field}\" on line {line}"
+                )
+            }
+            Self::MalformedLine { line, text } => {
+                write!(
+                    f,
+                    "Expected a \"key = value\" pair on line {line}, found {text:?}"
+                )
+            }
+            Self::MalformedJSON(msg) => write!(f, "Malformed periodic table JSON: {msg}"),
+            Self::UnknownElement(symbol) => {
+                write!(
+                    f,
+                    "Unknown element symbol {symbol:?}, its atomic number is not known"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PeriodicTableError {}
+
+impl From<io::Error> for PeriodicTableError {
+    fn from(value: io::Error) -> Self {
+        Self::IOError(value)
+    }
+}
+
+/// Unnaturally occurring isotopes which are still listed, like the tritium of `T`
+const LABEL_ISOTOPES: &[(&str, u16)] = &[("H", 3)];
+
+/// Build an [`Element`] from its isotopes, using the same conventions as the
+/// generated table. Isotopes with zero abundance are dropped unless they are
+/// [`LABEL_ISOTOPES`], and elements with no naturally occurring isotopes are
+/// represented by a single pseudo-isotope `0` carrying `reference_mass`. The
+/// element's name is taken from the built-in table.
+pub(crate) fn element_from_isotopes(
+    symbol: &str,
+    element_number: u8,
+    standard_atomic_weight: StandardAtomicWeight,
+    isotopes: Vec<Isotope>,
+    reference_mass: Option<f64>,
+) -> Option<Element> {
+    let mut isotopes: Vec<Isotope> = isotopes
+        .into_iter()
+        .filter(|iso| {
+            iso.neutrons != 0
+                && (iso.abundance > 0.0 || LABEL_ISOTOPES.contains(&(symbol, iso.neutrons)))
+        })
+        .collect();
+
+    let name = PERIODIC_TABLE
+        .get(symbol)
+        .or_else(|| PERIODIC_TABLE.by_number(element_number))
+        .map(|elt| elt.name.clone())
+        .unwrap_or_default();
+    let mut elt = Element {
+        symbol: symbol.to_string(),
+        element_number,
+        name,
+        standard_atomic_weight,
+        ..Default::default()
+    };
+
+    if let Some(most_abundant) = isotopes
+        .iter()
+        .max_by(|a, b| a.abundance.total_cmp(&b.abundance))
+        .cloned()
+    {
+        elt.most_abundant_isotope = most_abundant.neutrons;
+        elt.most_abundant_mass = most_abundant.mass;
+        for mut iso in isotopes.drain(..) {
+            iso.neutron_shift = (iso.neutrons as i32 - most_abundant.neutrons as i32) as i8;
+            elt.isotopes.insert(iso.neutrons, iso);
+        }
+    } else {
+        let mass = reference_mass?;
+        elt.most_abundant_mass = mass;
+        elt.isotopes.insert(
+            0,
+            Isotope {
+                mass,
+                abundance: 1.0,
+                neutrons: 0,
+                neutron_shift: 0,
+            },
+        );
+    }
+    elt.index_isotopes();
+    Some(elt)
+}
+
+/// Parse a NIST-style number like `12.0107(8)` or `294.21392(71#)`, discarding
+/// the uncertainty.
+fn parse_nist_number(value: &str) -> Option<f64> {
+    let value = match value.find('(') {
+        Some(i) => &value[..i],
+        None => value,
+    };
+    value.trim().trim_end_matches('#').parse().ok()
+}
+
+#[derive(Debug, Default)]
+struct NISTRecord {
+    atomic_number: u8,
+    symbol: String,
+    isotope: Isotope,
+    standard_atomic_weight: StandardAtomicWeight,
+    reference_mass: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct NISTRecordBuilder {
+    /// Maps each field name to the line it was read from and its value
+    fields: HashMap<String, (usize, String)>,
+}
+
+impl NISTRecordBuilder {
+    fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    fn get(&self, field: &str) -> Option<(usize, &str)> {
+        match self.fields.get(field) {
+            Some((line, val)) if !val.is_empty() => Some((*line, val.as_str())),
+            _ => None,
+        }
+    }
+
+    fn required(&self, field: &str, end: usize) -> Result<(usize, &str), PeriodicTableError> {
+        self.get(field)
+            .ok_or_else(|| PeriodicTableError::MissingField {
+                line: end,
+                field: field.to_string(),
+            })
+    }
+
+    fn build(&mut self, end: usize) -> Result<NISTRecord, PeriodicTableError> {
+        let malformed =
+            |field: &str, (line, value): (usize, &str)| PeriodicTableError::MalformedValue {
+                line,
+                field: field.to_string(),
+                value: value.to_string(),
+            };
+
+        let entry = self.required("Atomic Number", end)?;
+        let atomic_number: u8 = entry
+            .1
+            .parse()
+            .map_err(|_| malformed("Atomic Number", entry))?;
+
+        let symbol = self.required("Atomic Symbol", end)?.1.to_string();
+
+        let entry = self.required("Mass Number", end)?;
+        let mass_number: u16 = entry
+            .1
+            .parse()
+            .map_err(|_| malformed("Mass Number", entry))?;
+
+        let entry = self.required("Relative Atomic Mass", end)?;
+        let mass =
+            parse_nist_number(entry.1).ok_or_else(|| malformed("Relative Atomic Mass", entry))?;
+
+        let abundance = match self.get("Isotopic Composition") {
+            Some(entry) => parse_nist_number(entry.1)
+                .ok_or_else(|| malformed("Isotopic Composition", entry))?,
+            None => 0.0,
+        };
+
+        let standard_atomic_weight = match self.get("Standard Atomic Weight") {
+            Some(entry) => StandardAtomicWeight::from_nist(entry.1)
+                .ok_or_else(|| malformed("Standard Atomic Weight", entry))?,
+            None => StandardAtomicWeight::Unknown,
+        };
+        // Elements without a stable isotope list the mass number of their longest-lived
+        // isotope in brackets, e.g. `[98]`, which is used as a reference mass.
+        let reference_mass = match standard_atomic_weight {
+            StandardAtomicWeight::MassNumber(n) => Some(n as f64),
+            _ => None,
+        };
+
+        self.fields.clear();
+        Ok(NISTRecord {
+            atomic_number,
+            symbol,
+            isotope: Isotope {
+                mass,
+                abundance,
+                neutrons: mass_number,
+                neutron_shift: 0,
+            },
+            standard_atomic_weight,
+            reference_mass,
+        })
+    }
+}
+
+impl PeriodicTable {
+    /**
+    Read a [`PeriodicTable`] from the text listing produced by the NIST
+    [Atomic Weights and Isotopic Compositions](https://physics.nist.gov/cgi-bin/Compositions/stand_alone.pl)
+    service, as found in `data/nist_comp.txt`.
+
+    Records are grouped by atomic number, so isotopes listed under alternate
+    symbols like `D` and `T` are folded into the first symbol seen for that number.
+    Elements without any naturally abundant isotope or reference mass number are
+    omitted.
+    */
+    pub fn from_nist_text<R: BufRead>(reader: R) -> Result<PeriodicTable, PeriodicTableError> {
+        let mut records: Vec<NISTRecord> = Vec::new();
+        let mut builder = NISTRecordBuilder::default();
+        let mut line_number = 0;
+        for line in reader.lines() {
+            line_number += 1;
+            let line = line?;
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.is_empty() {
+                if !builder.is_empty() {
+                    records.push(builder.build(line_number)?);
+                }
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => {
+                    builder.fields.insert(
+                        key.trim().to_string(),
+                        (line_number, value.trim().to_string()),
+                    );
+                }
+                None => {
+                    return Err(PeriodicTableError::MalformedLine {
+                        line: line_number,
+                        text: line.to_string(),
+                    })
+                }
+            }
+        }
+        if !builder.is_empty() {
+            records.push(builder.build(line_number)?);
+        }
+
+        let mut groups: Vec<(NISTRecord, Vec<Isotope>)> = Vec::new();
+        for rec in records {
+            match groups
+                .iter_mut()
+                .find(|(head, _)| head.atomic_number == rec.atomic_number)
+            {
+                Some((head, isotopes)) => {
+                    isotopes.push(rec.isotope);
+                    if head.reference_mass.is_none() {
+                        head.reference_mass = rec.reference_mass;
+                    }
+                }
+                None => {
+                    let isotopes = vec![rec.isotope.clone()];
+                    groups.push((rec, isotopes));
+                }
+            }
+        }
+
+        let mut table = PeriodicTable::new();
+        for (head, isotopes) in groups {
+            if let Some(elt) = element_from_isotopes(
+                &head.symbol,
+                head.atomic_number,
+                head.standard_atomic_weight,
+                isotopes,
+                head.reference_mass,
+            ) {
+                table.add(elt);
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod json {
+    use super::*;
+    use serde_json::{Map, Value};
+
+    fn malformed(msg: String) -> PeriodicTableError {
+        PeriodicTableError::MalformedJSON(msg)
+    }
+
+    impl PeriodicTable {
+        /**
+        Read a [`PeriodicTable`] from JSON laid out like `data/nist_mass.json`,
+        a mapping from element symbol to a mapping from isotope number to a
+        `[mass, abundance]` pair. Isotope `0` holds the reference mass used when
+        the element has no naturally abundant isotope.
+
+        Atomic numbers are taken from the built-in table, so every symbol must be
+        found there or [`PeriodicTableError::UnknownElement`] is returned.
+        */
+        pub fn from_json<R: Read>(reader: R) -> Result<PeriodicTable, PeriodicTableError> {
+            let doc: Value =
+                serde_json::from_reader(reader).map_err(|e| malformed(e.to_string()))?;
+            let elements = doc
+                .as_object()
+                .ok_or_else(|| malformed("expected a top-level object".to_string()))?;
+
+            let mut table = PeriodicTable::new();
+            for (symbol, entries) in elements {
+                let entries = entries.as_object().ok_or_else(|| {
+                    malformed(format!("expected an object of isotopes for {symbol}"))
+                })?;
+                let mut isotopes = Vec::with_capacity(entries.len());
+                let mut reference_mass = None;
+                for (isonum, pair) in entries {
+                    let neutrons: u16 = isonum.parse().map_err(|_| {
+                        malformed(format!("invalid isotope number {isonum:?} for {symbol}"))
+                    })?;
+                    let (mass, abundance) = match pair.as_array().map(|v| v.as_slice()) {
+                        Some([mass, abundance]) => match (mass.as_f64(), abundance.as_f64()) {
+                            (Some(mass), Some(abundance)) => (mass, abundance),
+                            _ => {
+                                return Err(malformed(format!(
+                                    "non-numeric mass or abundance for {symbol}[{isonum}]"
+                                )))
+                            }
+                        },
+                        _ => {
+                            return Err(malformed(format!(
+                                "expected a [mass, abundance] pair for {symbol}[{isonum}]"
+                            )))
+                        }
+                    };
+                    if neutrons == 0 {
+                        reference_mass = Some(mass);
+                    } else {
+                        isotopes.push(Isotope {
+                            mass,
+                            abundance,
+                            neutrons,
+                            neutron_shift: 0,
+                        });
+                    }
+                }
+                // The JSON layout carries no atomic number or standard atomic weight,
+                // so these are borrowed from the built-in table
+                let (element_number, standard_atomic_weight) = PERIODIC_TABLE
+                    .get(symbol)
+                    .map(|elt| (elt.element_number, elt.standard_atomic_weight))
+                    .ok_or_else(|| PeriodicTableError::UnknownElement(symbol.clone()))?;
+                match element_from_isotopes(
+                    symbol,
+                    element_number,
+                    standard_atomic_weight,
+                    isotopes,
+                    reference_mass,
+                ) {
+                    Some(elt) => table.add(elt),
+                    None => {
+                        return Err(malformed(format!(
+                            "{symbol} has no abundant isotopes or reference mass"
+                        )))
+                    }
+                }
+            }
+            Ok(table)
+        }
+
+        /// Write this [`PeriodicTable`] as JSON in the layout read by [`PeriodicTable::from_json`]
+        pub fn write_json<W: Write>(&self, writer: W) -> Result<(), PeriodicTableError> {
+            let mut doc = Map::new();
+            for (symbol, elt) in self.elements.iter() {
+                let mut entries = Map::new();
+                entries.insert(
+                    "0".to_string(),
+                    Value::from(vec![elt.most_abundant_mass, 1.0]),
+                );
+                for (isonum, iso) in elt.isotopes.iter() {
+                    entries.insert(
+                        isonum.to_string(),
+                        Value::from(vec![iso.mass, iso.abundance]),
+                    );
+                }
+                doc.insert(symbol.clone(), Value::Object(entries));
+            }
+            serde_json::to_writer_pretty(writer, &doc).map_err(|e| match e.io_error_kind() {
+                Some(kind) => PeriodicTableError::IOError(io::Error::new(kind, e)),
+                None => malformed(e.to_string()),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PERIODIC_TABLE;
+
+    #[test]
+    fn test_from_nist_text() {
+        let table = PeriodicTable::from_nist_text(include_str!("../data/nist_comp.txt").as_bytes())
+            .unwrap();
+        let carbon = table.get("C").unwrap();
+        assert_eq!(carbon.most_abundant_isotope, 12);
+        assert_eq!(carbon.isotopes.len(), 2);
+        assert!((carbon.most_abundant_mass - PERIODIC_TABLE["C"].most_abundant_mass).abs() < 1e-6);
+
+        let hydrogen = table.get("H").unwrap();
+        assert_eq!(hydrogen.isotopes.len(), 3);
+        assert_eq!(hydrogen.isotopes[&3].abundance, 0.0);
+        assert_eq!(hydrogen.max_neutron_shift, 2);
+        assert!(table.get("D").is_none());
+
+        let technetium = table.get("Tc").unwrap();
+        assert_eq!(technetium.most_abundant_mass, 98.0);
+        assert_eq!(technetium.element_number, 43);
+        assert_eq!(
+            technetium.standard_atomic_weight,
+            StandardAtomicWeight::MassNumber(98)
+        );
+
+        assert_eq!(hydrogen.element_number, 1);
+        assert_eq!(hydrogen.name, "Hydrogen");
+        assert_eq!(
+            hydrogen.standard_atomic_weight,
+            PERIODIC_TABLE["H"].standard_atomic_weight
+        );
+        assert_eq!(table.by_number(6).unwrap().symbol, "C");
+    }
+
+    #[test]
+    fn test_nist_text_errors() {
+        let text = "Atomic Number = 6\nAtomic Symbol = C\nMass Number = 12\nRelative Atomic Mass = twelve\n";
+        let err = PeriodicTable::from_nist_text(text.as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            PeriodicTableError::MalformedValue { line: 4, .. }
+        ));
+
+        let text = "Atomic Number = 6\nMass Number = 12\n\n";
+        let err = PeriodicTable::from_nist_text(text.as_bytes()).unwrap_err();
+        assert!(matches!(err, PeriodicTableError::MissingField { .. }));
+
+        let text = "Atomic Number 6\n";
+        let err = PeriodicTable::from_nist_text(text.as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            PeriodicTableError::MalformedLine { line: 1, .. }
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let table =
+            PeriodicTable::from_json(include_str!("../data/nist_mass.json").as_bytes()).unwrap();
+        assert_eq!(table.elements.len(), PERIODIC_TABLE.elements.len());
+        for sym in ["C", "H", "O", "N", "S", "Fe", "Tc"] {
+            let elt = table.get(sym).unwrap();
+            let ctrl = &PERIODIC_TABLE[sym];
+            assert_eq!(elt.most_abundant_isotope, ctrl.most_abundant_isotope);
+            assert_eq!(elt.isotopes.len(), ctrl.isotopes.len());
+            assert!((elt.most_abundant_mass - ctrl.most_abundant_mass).abs() < 1e-6);
+            assert_eq!(elt.element_number, ctrl.element_number);
+        }
+
+        let mut buffer = Vec::new();
+        table.write_json(&mut buffer).unwrap();
+        let dup = PeriodicTable::from_json(buffer.as_slice()).unwrap();
+        assert_eq!(dup.elements.len(), table.elements.len());
+        assert_eq!(dup["S"], table["S"]);
+
+        let err = PeriodicTable::from_json(r#"{"C": {"12": [12.0]}}"#.as_bytes()).unwrap_err();
+        assert!(matches!(err, PeriodicTableError::MalformedJSON(_)));
+
+        let err =
+            PeriodicTable::from_json(r#"{"Qx": {"12": [12.0, 1.0]}}"#.as_bytes()).unwrap_err();
+        assert!(matches!(err, PeriodicTableError::UnknownElement(s) if s == "Qx"));
+    }
+}